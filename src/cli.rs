@@ -1,6 +1,6 @@
 use std::path::PathBuf;
 
-use clap::{Args, Parser, Subcommand};
+use clap::{Args, Parser, Subcommand, ValueEnum};
 
 #[derive(Parser)]
 #[command(version, about = "CLI scheduler backed by SQLite")]
@@ -25,12 +25,30 @@ pub enum Command {
     /// Move/adjust an existing schedule entry
     #[command(alias = "mv")]
     Move(MoveCommand),
+    /// Change the title, note, tags, visibility, location, link, status or reminders of an existing entry
+    Edit(EditCommand),
     /// Emit events as an RSS feed
     Rss(RssCommand),
     /// Emit an iCalendar (.ics) feed
     Ical(IcalCommand),
+    /// Emit a VFREEBUSY block listing busy times without event details
+    Freebusy(FreebusyCommand),
     /// Import events from an .ics file
     Import(ImportCommand),
+    /// Full-text search over titles and notes
+    Search(SearchCommand),
+    /// Show a month grid marking the days that have events
+    Cal(CalCommand),
+    /// Show a seven-day agenda with hourly rows
+    Week(WeekCommand),
+    /// Browse and edit events in a full-screen calendar
+    Tui(TuiCommand),
+    /// Report timed events that overlap each other
+    Conflicts(ConflictsCommand),
+    /// Find free slots within working hours
+    Free(FreeCommand),
+    /// Deliver due reminders once or keep watching for them
+    Remind(RemindCommand),
 }
 
 #[derive(Args)]
@@ -59,16 +77,158 @@ pub struct AddCommand {
     /// Store event as all-day entry (start/end treated as dates)
     #[arg(long, short = 'a')]
     pub all_day: bool,
+    /// Keep the event out of RSS and iCalendar exports
+    #[arg(long)]
+    pub private: bool,
+    /// Where the event takes place
+    #[arg(long)]
+    pub location: Option<String>,
+    /// Link for joining or reading about the event
+    #[arg(long)]
+    pub url: Option<String>,
+    /// tentative, confirmed or cancelled
+    #[arg(long)]
+    pub status: Option<String>,
+    /// Remind this long before the start, e.g. 10m or 1d (repeatable)
+    #[arg(long = "remind", value_name = "DURATION", action = clap::ArgAction::Append)]
+    pub reminders: Vec<String>,
     /// Duration syntax like 30m, 2h, 1h30m; ignored when --end is provided
     #[arg(long, short = 'u')]
     pub duration: Option<String>,
+    /// Repeat the event: daily, weekly, monthly or yearly
+    #[arg(long, short = 'r')]
+    pub repeat: Option<String>,
+    /// Repeat every N periods (e.g. --repeat weekly --interval 2 for fortnightly)
+    #[arg(long, requires = "repeat")]
+    pub interval: Option<u32>,
+    /// Weekdays for the rule, comma separated (MO,WE,FR; 1MO or -1FR for monthly/yearly)
+    #[arg(long = "by-day", requires = "repeat", value_delimiter = ',')]
+    pub by_day: Vec<String>,
+    /// Stop after this many occurrences
+    #[arg(long, requires = "repeat", conflicts_with = "until")]
+    pub count: Option<u32>,
+    /// Last date the series may occur on (YYYY-MM-DD or relative token)
+    #[arg(long, requires = "repeat")]
+    pub until: Option<String>,
+    #[command(flatten)]
+    pub overlap: OverlapArgs,
+    /// Report the result as text or JSON
+    #[arg(long, value_enum, default_value_t = ReportFormat::Text)]
+    pub format: ReportFormat,
+}
+
+/// How `add` and `move` treat double-bookings.
+#[derive(Args, Default)]
+pub struct OverlapArgs {
+    /// Refuse to store an event that overlaps another timed event
+    #[arg(long, conflicts_with = "allow_overlap")]
+    pub strict: bool,
+    /// Don't warn about overlapping timed events
+    #[arg(long)]
+    pub allow_overlap: bool,
+}
+
+/// Output of `list`: the human layout or one of the machine-readable ones.
+#[derive(Clone, Copy, ValueEnum)]
+pub enum ListFormat {
+    Text,
+    Json,
+    Ndjson,
+    Csv,
+    Tsv,
+}
+
+/// Output of commands that change events.
+#[derive(Clone, Copy, ValueEnum)]
+pub enum ReportFormat {
+    Text,
+    Json,
+}
+
+/// How feeds treat events marked private.
+#[derive(Clone, Copy, ValueEnum)]
+pub enum PrivateEvents {
+    Skip,
+    Busy,
+}
+
+/// Time window shared by commands that read a span of the calendar.
+#[derive(Args, Default)]
+pub struct RangeArgs {
+    /// Filter by a specific day in the display timezone e.g. 2025-06-01
+    #[arg(long, short = 'd', conflicts_with_all = ["from", "to", "week", "month", "upcoming"])]
+    pub day: Option<String>,
+    /// Start of the window: a date (YYYY-MM-DD, today, -7d...) or RFC3339 instant
+    #[arg(long, conflicts_with_all = ["week", "month", "upcoming"])]
+    pub from: Option<String>,
+    /// End of the window; a date includes that whole day
+    #[arg(long, conflicts_with_all = ["week", "month", "upcoming"])]
+    pub to: Option<String>,
+    /// The Monday-to-Sunday week containing DATE (default: this week)
+    #[arg(
+        long,
+        value_name = "DATE",
+        num_args = 0..=1,
+        default_missing_value = "today",
+        conflicts_with_all = ["month", "upcoming"]
+    )]
+    pub week: Option<String>,
+    /// A calendar month as YYYY-MM, or the month of a date (default: this month)
+    #[arg(
+        long,
+        value_name = "YYYY-MM",
+        num_args = 0..=1,
+        default_missing_value = "today",
+        conflicts_with = "upcoming"
+    )]
+    pub month: Option<String>,
+    /// From now until now + DURATION, e.g. 14d or 3h
+    #[arg(long, value_name = "DURATION")]
+    pub upcoming: Option<String>,
+    /// Compute day boundaries at UTC midnight instead of in the display timezone
+    #[arg(long)]
+    pub utc_day: bool,
+}
+
+/// Tag filters shared by the commands that select events in bulk.
+#[derive(Args, Default)]
+pub struct TagArgs {
+    /// Only events carrying this tag (repeatable)
+    #[arg(long = "tag", value_name = "TAG")]
+    pub tags: Vec<String>,
+    /// Leave out events carrying this tag (repeatable)
+    #[arg(long = "exclude-tag", value_name = "TAG")]
+    pub exclude_tags: Vec<String>,
+    /// Match events carrying any of the --tag values (the default)
+    #[arg(long, requires = "tags", conflicts_with = "all_tags")]
+    pub any_tag: bool,
+    /// Match only events carrying every --tag value
+    #[arg(long, requires = "tags")]
+    pub all_tags: bool,
+}
+
+impl TagArgs {
+    pub fn is_empty(&self) -> bool {
+        self.tags.is_empty() && self.exclude_tags.is_empty()
+    }
 }
 
 #[derive(Args)]
 pub struct ListCommand {
-    /// Filter by a specific day (UTC) e.g. 2025-06-01
-    #[arg(long, short = 'd')]
-    pub day: Option<String>,
+    #[command(flatten)]
+    pub range: RangeArgs,
+    #[command(flatten)]
+    pub tags: TagArgs,
+    /// Only events whose title or note matches this full-text query
+    #[arg(long, short = 'q')]
+    pub query: Option<String>,
+    /// Output format
+    #[arg(long, short = 'f', value_enum, default_value_t = ListFormat::Text)]
+    pub format: ListFormat,
+    /// Layout for each event, e.g. '{start:%H:%M} {title} [{tags}]', or the name of a
+    /// template from config.toml
+    #[arg(long, short = 'T', conflicts_with = "format")]
+    pub template: Option<String>,
     /// Timezone for display, e.g. Europe/Paris; defaults to local system zone
     #[arg(long = "tz", short = 'z')]
     pub tz: Option<String>,
@@ -76,9 +236,10 @@ pub struct ListCommand {
 
 #[derive(Args)]
 pub struct RssCommand {
-    /// Optional day filter (UTC)
-    #[arg(long, short = 'd')]
-    pub day: Option<String>,
+    #[command(flatten)]
+    pub range: RangeArgs,
+    #[command(flatten)]
+    pub tags: TagArgs,
     /// Override timezone used inside descriptions
     #[arg(long = "tz", short = 'z')]
     pub tz: Option<String>,
@@ -91,6 +252,9 @@ pub struct RssCommand {
     /// Channel description
     #[arg(long)]
     pub description: Option<String>,
+    /// Leave private events out, or show them as untitled busy items
+    #[arg(long, value_enum, default_value_t = PrivateEvents::Skip)]
+    pub private_events: PrivateEvents,
     /// Write RSS XML to this file instead of stdout
     #[arg(long, short = 'o')]
     pub output: Option<PathBuf>,
@@ -98,25 +262,195 @@ pub struct RssCommand {
 
 #[derive(Args)]
 pub struct IcalCommand {
-    /// Optional day filter (UTC)
-    #[arg(long, short = 'd')]
-    pub day: Option<String>,
+    #[command(flatten)]
+    pub range: RangeArgs,
+    #[command(flatten)]
+    pub tags: TagArgs,
     /// Override timezone used for timed events
     #[arg(long = "tz", short = 'z')]
     pub tz: Option<String>,
+    /// Replace every title and description with "Busy"
+    #[arg(long)]
+    pub busy_only: bool,
+    /// Leave private events out, or export them as busy blocks marked CLASS:PRIVATE
+    #[arg(long, value_enum, default_value_t = PrivateEvents::Skip)]
+    pub private_events: PrivateEvents,
     /// Write ICS to this file instead of stdout
     #[arg(long, short = 'o')]
     pub output: Option<PathBuf>,
 }
 
+#[derive(Args)]
+pub struct FreebusyCommand {
+    #[command(flatten)]
+    pub range: RangeArgs,
+    /// Only events with these tags count as busy
+    #[command(flatten)]
+    pub tags: TagArgs,
+    /// Timezone used to resolve the range; defaults to local system zone
+    #[arg(long = "tz", short = 'z')]
+    pub tz: Option<String>,
+    /// Treat all-day events as blocking their whole days
+    #[arg(long)]
+    pub all_day_busy: bool,
+    /// Write ICS to this file instead of stdout
+    #[arg(long, short = 'o')]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Args)]
+pub struct SearchCommand {
+    /// FTS5 query, e.g. budget, "quarterly review" or budget NOT draft
+    pub query: String,
+    #[command(flatten)]
+    pub range: RangeArgs,
+    #[command(flatten)]
+    pub tags: TagArgs,
+    /// Timezone for display, e.g. Europe/Paris; defaults to local system zone
+    #[arg(long = "tz", short = 'z')]
+    pub tz: Option<String>,
+    /// Maximum number of results
+    #[arg(long, short = 'n', default_value_t = 20)]
+    pub limit: usize,
+}
+
+/// Display options shared by the calendar views.
+#[derive(Args, Default)]
+pub struct LayoutArgs {
+    /// Timezone for display, e.g. Europe/Paris; defaults to local system zone
+    #[arg(long = "tz", short = 'z')]
+    pub tz: Option<String>,
+    /// First day of the week, e.g. monday or sunday (default: config, else monday)
+    #[arg(long, value_name = "DAY")]
+    pub week_start: Option<String>,
+    /// Lay out for this many columns instead of the terminal width
+    #[arg(long, short = 'w')]
+    pub width: Option<usize>,
+}
+
+#[derive(Args)]
+pub struct CalCommand {
+    /// Month to show as YYYY-MM, or the month of a date (default: this month)
+    #[arg(value_name = "MONTH")]
+    pub month: Option<String>,
+    #[command(flatten)]
+    pub tags: TagArgs,
+    #[command(flatten)]
+    pub layout: LayoutArgs,
+}
+
+#[derive(Args)]
+pub struct WeekCommand {
+    /// Any date in the week to show (default: today)
+    #[arg(value_name = "DATE")]
+    pub date: Option<String>,
+    #[command(flatten)]
+    pub tags: TagArgs,
+    #[command(flatten)]
+    pub layout: LayoutArgs,
+}
+
+#[derive(Args)]
+pub struct ConflictsCommand {
+    #[command(flatten)]
+    pub range: RangeArgs,
+    #[command(flatten)]
+    pub tags: TagArgs,
+    /// Timezone for display, e.g. Europe/Paris; defaults to local system zone
+    #[arg(long = "tz", short = 'z')]
+    pub tz: Option<String>,
+    /// Print the pairs as text or JSON
+    #[arg(long, short = 'f', value_enum, default_value_t = ReportFormat::Text)]
+    pub format: ReportFormat,
+}
+
+#[derive(Args)]
+pub struct FreeCommand {
+    #[command(flatten)]
+    pub range: RangeArgs,
+    /// Only events with these tags count as busy
+    #[command(flatten)]
+    pub tags: TagArgs,
+    /// Timezone for working hours and display; defaults to local system zone
+    #[arg(long = "tz", short = 'z')]
+    pub tz: Option<String>,
+    /// Working hours as HH:MM-HH:MM (default: config, else 09:00-18:00)
+    #[arg(long, value_name = "HH:MM-HH:MM")]
+    pub hours: Option<String>,
+    /// Working days, comma separated, e.g. mon,tue,thu (default: config, else mon-fri)
+    #[arg(long, value_delimiter = ',')]
+    pub days: Vec<String>,
+    /// Shortest slot worth reporting, e.g. 45m (default: config, else 30m)
+    #[arg(long, short = 'm', value_name = "DURATION")]
+    pub min: Option<String>,
+    /// Time kept clear before and after each event, e.g. 10m (default: config, else none)
+    #[arg(long, value_name = "DURATION")]
+    pub buffer: Option<String>,
+    /// Treat all-day events as blocking their whole days
+    #[arg(long)]
+    pub all_day_busy: bool,
+    /// Print the slots as text or JSON
+    #[arg(long, short = 'f', value_enum, default_value_t = ReportFormat::Text)]
+    pub format: ReportFormat,
+}
+
+#[derive(Args)]
+pub struct RemindCommand {
+    /// Keep running, checking for due reminders every --interval
+    #[arg(long, conflicts_with = "check")]
+    pub daemon: bool,
+    /// Deliver the reminders that are due now and exit (for cron or systemd timers)
+    #[arg(long)]
+    pub check: bool,
+    /// Shell command run for each reminder; {title}, {start} etc. expand to the
+    /// TOKI_NOTE_* variables (default: config, else print to stdout)
+    #[arg(long)]
+    pub command: Option<String>,
+    /// Reminder for events without their own, e.g. 10m (default: config, else none)
+    #[arg(long, value_name = "DURATION")]
+    pub default_offset: Option<String>,
+    /// Time between checks with --daemon (default: config, else 30s)
+    #[arg(long, value_name = "DURATION")]
+    pub interval: Option<String>,
+    /// Timezone for {start}/{end} and all-day events; defaults to local system zone
+    #[arg(long = "tz", short = 'z')]
+    pub tz: Option<String>,
+}
+
+#[derive(Args)]
+pub struct TuiCommand {
+    /// Date to open on (default: today)
+    #[arg(value_name = "DATE")]
+    pub date: Option<String>,
+    #[command(flatten)]
+    pub tags: TagArgs,
+    /// Timezone for display, e.g. Europe/Paris; defaults to local system zone
+    #[arg(long = "tz", short = 'z')]
+    pub tz: Option<String>,
+    /// First day of the week, e.g. monday or sunday (default: config, else monday)
+    #[arg(long, value_name = "DAY")]
+    pub week_start: Option<String>,
+}
+
 #[derive(Args)]
 pub struct DeleteCommand {
     /// Numeric event id to remove
-    #[arg(long, short = 'i')]
+    #[arg(long, short = 'i', conflicts_with_all = ["tags", "exclude_tags"])]
     pub id: Option<i64>,
     /// Event title to remove (deletes matching rows)
-    #[arg(long, short = 't')]
+    #[arg(long, short = 't', conflicts_with_all = ["tags", "exclude_tags"])]
     pub title: Option<String>,
+    /// Cancel only the occurrence of a repeating event on this date
+    #[arg(long, short = 'o')]
+    pub occurrence: Option<String>,
+    /// With --occurrence, also remove every later occurrence
+    #[arg(long, requires = "occurrence")]
+    pub this_and_following: bool,
+    #[command(flatten)]
+    pub tags: TagArgs,
+    /// Report the result as text or JSON
+    #[arg(long, value_enum, default_value_t = ReportFormat::Text)]
+    pub format: ReportFormat,
 }
 
 #[derive(Args)]
@@ -142,11 +476,92 @@ pub struct MoveCommand {
     /// Duration syntax like 30m, 2h, 1h30m; ignored when --end is provided
     #[arg(long, short = 'u')]
     pub duration: Option<String>,
+    /// Move only the occurrence of a repeating event on this date
+    #[arg(long, short = 'o')]
+    pub occurrence: Option<String>,
+    /// With --occurrence, move that occurrence and every later one
+    #[arg(long, requires = "occurrence")]
+    pub this_and_following: bool,
+    #[command(flatten)]
+    pub overlap: OverlapArgs,
+    /// Report the result as text or JSON
+    #[arg(long, value_enum, default_value_t = ReportFormat::Text)]
+    pub format: ReportFormat,
+}
+
+#[derive(Args)]
+pub struct EditCommand {
+    /// Numeric event id to edit
+    #[arg(long, short = 'i')]
+    pub id: i64,
+    /// Replace the title
+    #[arg(long, short = 't')]
+    pub title: Option<String>,
+    /// Replace the note
+    #[arg(long, short = 'n')]
+    pub note: Option<String>,
+    /// Append a line to the note (applied after --note)
+    #[arg(long)]
+    pub append_note: Option<String>,
+    /// Tag to add (repeatable)
+    #[arg(long = "add-tag", action = clap::ArgAction::Append)]
+    pub add_tags: Vec<String>,
+    /// Tag to remove (repeatable)
+    #[arg(long = "remove-tag", action = clap::ArgAction::Append)]
+    pub remove_tags: Vec<String>,
+    /// Remove every tag before applying --add-tag
+    #[arg(long)]
+    pub clear_tags: bool,
+    /// Keep the event out of RSS and iCalendar exports
+    #[arg(long, conflicts_with = "public")]
+    pub private: bool,
+    /// Let a private event appear in exports again
+    #[arg(long)]
+    pub public: bool,
+    /// Replace the location (empty to clear)
+    #[arg(long)]
+    pub location: Option<String>,
+    /// Replace the link (empty to clear)
+    #[arg(long)]
+    pub url: Option<String>,
+    /// Set the status: tentative, confirmed or cancelled (empty to clear)
+    #[arg(long)]
+    pub status: Option<String>,
+    /// Reminder to add, e.g. 10m or 1d before the start (repeatable)
+    #[arg(long = "remind", value_name = "DURATION", action = clap::ArgAction::Append)]
+    pub reminders: Vec<String>,
+    /// Remove every reminder before applying --remind
+    #[arg(long)]
+    pub clear_reminders: bool,
+    /// Open the whole event in $VISUAL/$EDITOR as a TOML document
+    #[arg(
+        long,
+        conflicts_with_all = [
+            "title", "note", "append_note", "add_tags", "remove_tags", "clear_tags", "private",
+            "public", "location", "url", "status", "reminders", "clear_reminders"
+        ]
+    )]
+    pub interactive: bool,
 }
 
 #[derive(Args)]
 pub struct ImportCommand {
-    /// Path to the .ics file to import
+    /// The .ics file to import, `-` for stdin, or a directory or glob whose
+    /// .ics files are all imported
     #[arg(long = "path", short = 'p')]
     pub path: Option<PathBuf>,
+    /// Report what each event would do (new, duplicate, would-update or
+    /// invalid, with the reason) without writing anything
+    #[arg(long)]
+    pub dry_run: bool,
+    /// Write everything in one transaction and roll it all back on the first
+    /// invalid event or error
+    #[arg(long)]
+    pub atomic: bool,
+    /// Events written per transaction otherwise (default: 1000)
+    #[arg(long, value_name = "N", conflicts_with = "atomic")]
+    pub batch_size: Option<usize>,
+    /// Report the result as text or JSON
+    #[arg(long, value_enum, default_value_t = ReportFormat::Text)]
+    pub format: ReportFormat,
 }
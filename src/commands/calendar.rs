@@ -0,0 +1,565 @@
+use anyhow::{Result, anyhow};
+use chrono::{Datelike, Days, Duration, Months, NaiveDate, NaiveDateTime, Timelike, Utc, Weekday};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+use crate::{
+    cli::{CalCommand, LayoutArgs, WeekCommand},
+    storage::{Storage, StoredEvent},
+};
+
+use super::{
+    events::{DisplayZone, format_event_timing, parse_date, parse_timezone, parse_utc},
+    range::{date_range, event_filter, parse_month},
+};
+
+/// Width used when neither `--width` nor the terminal reports one.
+const DEFAULT_WIDTH: usize = 80;
+/// Timed events listed under each day of the month grid.
+const MONTH_TIMED_LINES: usize = 2;
+/// Hours the week view always shows, even when nothing is scheduled.
+const WORKDAY_HOURS: (u32, u32) = (8, 18);
+/// Width of the `HH:00 ` column in the week view.
+const HOUR_GUTTER: usize = 6;
+
+pub fn show_month(storage: &Storage, cmd: CalCommand) -> Result<()> {
+    let tz = parse_timezone(cmd.layout.tz.as_deref())?;
+    let week_start = parse_week_start(cmd.layout.week_start.as_deref())?;
+    let first = match cmd.month.as_deref() {
+        Some(month) => parse_month(month)?,
+        None => {
+            let today = tz.date_of(Utc::now());
+            today.with_day(1).unwrap_or(today)
+        }
+    };
+    let next = first
+        .checked_add_months(Months::new(1))
+        .ok_or_else(|| anyhow!("date overflow"))?;
+
+    let range = date_range(first, next, &tz)?;
+    let events = storage.fetch_events(&event_filter(Some(range), &cmd.tags))?;
+    let spans = place_events(&events, &tz)?;
+
+    for line in month_grid(first, week_start, &spans, layout_width(&cmd.layout)) {
+        println!("{line}");
+    }
+    print_agenda(&events, &tz)
+}
+
+pub fn show_week(storage: &Storage, cmd: WeekCommand) -> Result<()> {
+    let tz = parse_timezone(cmd.layout.tz.as_deref())?;
+    let week_start = parse_week_start(cmd.layout.week_start.as_deref())?;
+    let date = match cmd.date.as_deref() {
+        Some(date) => parse_date(date)?,
+        None => tz.date_of(Utc::now()),
+    };
+    let first = week_containing(date, week_start)?;
+    let next = first
+        .checked_add_days(Days::new(7))
+        .ok_or_else(|| anyhow!("date overflow"))?;
+
+    let range = date_range(first, next, &tz)?;
+    let events = storage.fetch_events(&event_filter(Some(range), &cmd.tags))?;
+    let spans = place_events(&events, &tz)?;
+
+    for line in week_grid(first, &spans, layout_width(&cmd.layout)) {
+        println!("{line}");
+    }
+    print_agenda(&events, &tz)
+}
+
+/// Lists the events behind a grid with their full timing, since the grid
+/// itself has to truncate titles.
+fn print_agenda(events: &[StoredEvent], zone: &DisplayZone) -> Result<()> {
+    if events.is_empty() {
+        return Ok(());
+    }
+    println!();
+    for event in events {
+        println!(
+            "#{} {}  {}",
+            event.id,
+            event.title,
+            format_event_timing(event, zone)?
+        );
+    }
+    Ok(())
+}
+
+pub(super) fn parse_week_start(value: Option<&str>) -> Result<Weekday> {
+    match value {
+        None => Ok(Weekday::Mon),
+        Some(value) => value.trim().parse().map_err(|_| {
+            anyhow!("unknown week start '{value}'; expected a weekday such as monday or sunday")
+        }),
+    }
+}
+
+fn layout_width(args: &LayoutArgs) -> usize {
+    args.width
+        .or_else(|| terminal_size::terminal_size().map(|(width, _)| width.0 as usize))
+        .or_else(|| std::env::var("COLUMNS").ok()?.parse().ok())
+        .unwrap_or(DEFAULT_WIDTH)
+}
+
+pub(super) fn week_containing(date: NaiveDate, week_start: Weekday) -> Result<NaiveDate> {
+    date.checked_sub_days(Days::new(date.weekday().days_since(week_start) as u64))
+        .ok_or_else(|| anyhow!("date overflow"))
+}
+
+/// An event pinned to the days it covers in the display zone.
+struct Placed<'a> {
+    event: &'a StoredEvent,
+    /// First and last day covered, both inclusive.
+    first: NaiveDate,
+    last: NaiveDate,
+    /// Wall-clock start and end; unused for all-day events.
+    start: NaiveDateTime,
+    end: NaiveDateTime,
+}
+
+impl Placed<'_> {
+    /// All-day and multi-day events are drawn as bars across cells.
+    fn is_bar(&self) -> bool {
+        self.event.all_day || self.last > self.first
+    }
+
+    fn covers(&self, date: NaiveDate) -> bool {
+        self.first <= date && date <= self.last
+    }
+}
+
+/// First and last day (inclusive) `event` covers. All-day events keep their
+/// stored UTC dates, as `list` shows them; timed events land on the days
+/// they touch in `zone`.
+pub(super) fn event_days(
+    event: &StoredEvent,
+    zone: &DisplayZone,
+) -> Result<(NaiveDate, NaiveDate)> {
+    let start = parse_utc(&event.starts_at)?;
+    let end = parse_utc(&event.ends_at)?;
+    let (first, last) = if event.all_day {
+        (start.date_naive(), (end - Duration::days(1)).date_naive())
+    } else {
+        (
+            zone.date_of(start),
+            zone.date_of(end - Duration::seconds(1)),
+        )
+    };
+    Ok((first, last.max(first)))
+}
+
+fn place_events<'a>(events: &'a [StoredEvent], zone: &DisplayZone) -> Result<Vec<Placed<'a>>> {
+    let mut placed = events
+        .iter()
+        .map(|event| {
+            let (first, last) = event_days(event, zone)?;
+            Ok(Placed {
+                event,
+                first,
+                last,
+                start: zone.naive(parse_utc(&event.starts_at)?),
+                end: zone.naive(parse_utc(&event.ends_at)?),
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    placed.sort_by(|a, b| {
+        (a.first, b.last, a.start, a.event.id).cmp(&(b.first, a.last, b.start, b.event.id))
+    });
+    Ok(placed)
+}
+
+/// A bar clipped to one row of cells.
+struct Bar<'a> {
+    title: &'a str,
+    /// Column range within the row, inclusive.
+    from: usize,
+    to: usize,
+    /// Whether the event continues before or after the row.
+    continues_before: bool,
+    continues_after: bool,
+}
+
+/// Packs the bars visible on `days` into lanes so that bars in one lane
+/// never share a cell. `days[i]` is `None` for cells left blank.
+fn bar_lanes<'a>(days: &[Option<NaiveDate>], spans: &'a [Placed<'a>]) -> Vec<Vec<Bar<'a>>> {
+    let mut lanes: Vec<Vec<Bar<'a>>> = Vec::new();
+    for span in spans.iter().filter(|span| span.is_bar()) {
+        let mut columns = days
+            .iter()
+            .enumerate()
+            .filter(|(_, day)| day.is_some_and(|day| span.covers(day)))
+            .map(|(column, _)| column);
+        let Some(from) = columns.next() else {
+            continue;
+        };
+        let to = columns.next_back().unwrap_or(from);
+        let bar = Bar {
+            title: &span.event.title,
+            from,
+            to,
+            continues_before: days[from].is_some_and(|day| span.first < day),
+            continues_after: days[to].is_some_and(|day| span.last > day),
+        };
+        match lanes
+            .iter_mut()
+            .find(|lane| lane.last().is_none_or(|last| last.to < bar.from))
+        {
+            Some(lane) => lane.push(bar),
+            None => lanes.push(vec![bar]),
+        }
+    }
+    lanes
+}
+
+/// Draws one lane as `[Trip=====]`, with `<` or `>` where the event runs
+/// past the row.
+fn render_lane(lane: &[Bar<'_>], columns: usize, cell: usize) -> String {
+    let mut line = String::new();
+    let mut column = 0;
+    for bar in lane {
+        line.push_str(&" ".repeat((bar.from - column) * cell));
+        let width = (bar.to - bar.from + 1) * cell;
+        let open = if bar.continues_before { '<' } else { '[' };
+        let close = if bar.continues_after { '>' } else { ']' };
+        line.push(' ');
+        line.push(open);
+        line.push_str(&fit(bar.title, width.saturating_sub(3), '='));
+        line.push(close);
+        column = bar.to + 1;
+    }
+    line.push_str(&" ".repeat(columns.saturating_sub(column) * cell));
+    line.trim_end().to_string()
+}
+
+fn month_grid(
+    first: NaiveDate,
+    week_start: Weekday,
+    spans: &[Placed<'_>],
+    width: usize,
+) -> Vec<String> {
+    let cell = (width / 7).clamp(4, 30);
+    let detailed = cell >= 8;
+    let mut lines = vec![
+        center(&first.format("%B %Y").to_string(), cell * 7),
+        weekday_header(week_start, cell),
+    ];
+
+    let mut row_start = week_containing(first, week_start).unwrap_or(first);
+    while row_start.year() < first.year()
+        || (row_start.year() == first.year() && row_start.month() <= first.month())
+    {
+        let days: Vec<Option<NaiveDate>> = (0..7)
+            .map(|offset| {
+                row_start
+                    .checked_add_days(Days::new(offset))
+                    .filter(|day| day.month() == first.month() && day.year() == first.year())
+            })
+            .collect();
+
+        let mut numbers = String::new();
+        for day in &days {
+            let text = match day {
+                Some(day) => {
+                    let marked = spans.iter().any(|span| span.covers(*day));
+                    format!(" {:>2}{}", day.day(), if marked { "*" } else { "" })
+                }
+                None => String::new(),
+            };
+            numbers.push_str(&fit(&text, cell, ' '));
+        }
+        lines.push(numbers.trim_end().to_string());
+
+        if detailed {
+            for lane in bar_lanes(&days, spans) {
+                lines.push(render_lane(&lane, 7, cell));
+            }
+            if cell >= 10 {
+                lines.extend(timed_lines(&days, spans, cell));
+            }
+        }
+
+        match row_start.checked_add_days(Days::new(7)) {
+            Some(next) => row_start = next,
+            None => break,
+        }
+        if detailed {
+            lines.push(String::new());
+        }
+    }
+    while lines.last().is_some_and(|line| line.is_empty()) {
+        lines.pop();
+    }
+    lines
+}
+
+/// Up to `MONTH_TIMED_LINES` timed events per day, with `+N more` when a
+/// day has more than fit.
+fn timed_lines(days: &[Option<NaiveDate>], spans: &[Placed<'_>], cell: usize) -> Vec<String> {
+    let per_day: Vec<Vec<&Placed<'_>>> = days
+        .iter()
+        .map(|day| {
+            spans
+                .iter()
+                .filter(|span| !span.is_bar() && day.is_some_and(|day| span.first == day))
+                .collect()
+        })
+        .collect();
+    let rows = per_day
+        .iter()
+        .map(|events| events.len().min(MONTH_TIMED_LINES))
+        .max()
+        .unwrap_or(0);
+
+    (0..rows)
+        .map(|row| {
+            let mut line = String::new();
+            for events in &per_day {
+                let overflow = events.len() > MONTH_TIMED_LINES && row == MONTH_TIMED_LINES - 1;
+                let text = if overflow {
+                    format!(" +{} more", events.len() - row)
+                } else if let Some(span) = events.get(row) {
+                    format!(" {} {}", span.start.format("%H:%M"), span.event.title)
+                } else {
+                    String::new()
+                };
+                line.push_str(&fit(&text, cell, ' '));
+            }
+            line.trim_end().to_string()
+        })
+        .collect()
+}
+
+fn week_grid(first: NaiveDate, spans: &[Placed<'_>], width: usize) -> Vec<String> {
+    let cell = (width.saturating_sub(HOUR_GUTTER) / 7).max(6);
+    let gutter = " ".repeat(HOUR_GUTTER);
+    let days: Vec<Option<NaiveDate>> = (0..7)
+        .map(|offset| first.checked_add_days(Days::new(offset)))
+        .collect();
+
+    let mut header = gutter.clone();
+    for day in days.iter().flatten() {
+        let text = if cell >= 10 {
+            day.format(" %a %m/%d").to_string()
+        } else {
+            day.format(" %a %d").to_string()
+        };
+        header.push_str(&fit(&text, cell, ' '));
+    }
+    let mut lines = vec![header.trim_end().to_string()];
+
+    for lane in bar_lanes(&days, spans) {
+        lines.push(format!("{gutter}{}", render_lane(&lane, 7, cell)));
+    }
+
+    let timed: Vec<&Placed<'_>> = spans
+        .iter()
+        .filter(|span| !span.is_bar() && days.contains(&Some(span.first)))
+        .collect();
+    let (mut from, mut to) = WORKDAY_HOURS;
+    for span in &timed {
+        from = from.min(span.start.hour());
+        let minutes = (span.end - span.first.and_time(Default::default())).num_minutes();
+        to = to.max(((minutes + 59) / 60).clamp(0, 24) as u32);
+    }
+
+    for hour in from..to {
+        let mut line = format!("{hour:02}:00 ");
+        for day in days.iter().flatten() {
+            let Some(slot) = day.and_hms_opt(hour, 0, 0) else {
+                line.push_str(&" ".repeat(cell));
+                continue;
+            };
+            let starting: Vec<&&Placed<'_>> = timed
+                .iter()
+                .filter(|span| span.first == *day && span.start.hour() == hour)
+                .collect();
+            let text = match starting.as_slice() {
+                [] if timed
+                    .iter()
+                    .any(|span| span.first == *day && span.start < slot && span.end > slot) =>
+                {
+                    " |".to_string()
+                }
+                [] => String::new(),
+                [span, rest @ ..] => {
+                    let text = format!(" {} {}", span.start.format("%H:%M"), span.event.title);
+                    if rest.is_empty() {
+                        text
+                    } else {
+                        let more = format!("+{}", rest.len());
+                        let room = cell.saturating_sub(more.len());
+                        format!("{}{more}", fit(&text, room, ' '))
+                    }
+                }
+            };
+            line.push_str(&fit(&text, cell, ' '));
+        }
+        lines.push(line.trim_end().to_string());
+    }
+    lines
+}
+
+fn weekday_header(week_start: Weekday, cell: usize) -> String {
+    let mut line = String::new();
+    let mut weekday = week_start;
+    for _ in 0..7 {
+        let name = weekday.to_string();
+        let name = if cell >= 6 { &name[..3] } else { &name[..2] };
+        line.push_str(&fit(&format!(" {name}"), cell, ' '));
+        weekday = weekday.succ();
+    }
+    line.trim_end().to_string()
+}
+
+fn center(text: &str, width: usize) -> String {
+    let pad = width.saturating_sub(text.width()) / 2;
+    format!("{}{text}", " ".repeat(pad))
+}
+
+/// Cuts `text` to exactly `width` terminal columns, ending with `…` when it
+/// had to be shortened and padding with `fill` otherwise.
+fn fit(text: &str, width: usize, fill: char) -> String {
+    let mut out = String::new();
+    let mut used = 0;
+    if text.width() > width {
+        for ch in text.chars() {
+            let ch = if ch.is_control() { ' ' } else { ch };
+            let w = ch.width().unwrap_or(0);
+            if used + w + 1 > width {
+                break;
+            }
+            out.push(ch);
+            used += w;
+        }
+        if width > 0 {
+            out.push('…');
+            used += 1;
+        }
+    } else {
+        out.extend(
+            text.chars()
+                .map(|ch| if ch.is_control() { ' ' } else { ch }),
+        );
+        used = text.width();
+    }
+    out.extend(std::iter::repeat_n(fill, width.saturating_sub(used)));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(id: i64, title: &str, start: &str, end: &str, all_day: bool) -> StoredEvent {
+        StoredEvent {
+            id,
+            title: title.into(),
+            starts_at: start.into(),
+            ends_at: end.into(),
+            all_day,
+            ..Default::default()
+        }
+    }
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn month_grid_marks_days_and_draws_bars_across_weeks() {
+        let events = vec![
+            event(
+                1,
+                "Trip",
+                "2025-08-08T00:00:00+00:00",
+                "2025-08-12T00:00:00+00:00",
+                true,
+            ),
+            event(
+                2,
+                "Standup",
+                "2025-08-20T00:30:00+00:00",
+                "2025-08-20T01:00:00+00:00",
+                false,
+            ),
+        ];
+        let tokyo = DisplayZone::Named(chrono_tz::Asia::Tokyo);
+        let spans = place_events(&events, &tokyo).unwrap();
+
+        let narrow = month_grid(date("2025-08-01"), Weekday::Sun, &spans, 28);
+        assert_eq!(
+            narrow,
+            vec![
+                "        August 2025",
+                " Su  Mo  Tu  We  Th  Fr  Sa",
+                "                      1   2",
+                "  3   4   5   6   7   8*  9*",
+                " 10* 11* 12  13  14  15  16",
+                " 17  18  19  20* 21  22  23",
+                " 24  25  26  27  28  29  30",
+                " 31",
+            ]
+        );
+
+        let wide = month_grid(date("2025-08-01"), Weekday::Mon, &spans, 84);
+        let trip_start = wide.iter().position(|line| line.contains("[Trip")).unwrap();
+        assert_eq!(
+            wide[trip_start],
+            format!("{}[Trip{}>", " ".repeat(49), "=".repeat(29))
+        );
+        assert_eq!(wide[trip_start + 3], " <Trip=====]");
+        // Tokyo is UTC+9, so the 00:30 UTC standup shows at 09:30.
+        assert!(wide.iter().any(|line| line.ends_with(" 09:30 Stan…")));
+    }
+
+    #[test]
+    fn week_grid_lays_out_hours_and_overlaps() {
+        let events = vec![
+            event(
+                1,
+                "Review",
+                "2025-08-12T07:00:00+00:00",
+                "2025-08-12T09:30:00+00:00",
+                false,
+            ),
+            event(
+                2,
+                "Call",
+                "2025-08-12T07:15:00+00:00",
+                "2025-08-12T07:45:00+00:00",
+                false,
+            ),
+            event(
+                3,
+                "Offsite",
+                "2025-08-14T00:00:00+00:00",
+                "2025-08-16T00:00:00+00:00",
+                true,
+            ),
+        ];
+        let utc = DisplayZone::Named(chrono_tz::UTC);
+        let spans = place_events(&events, &utc).unwrap();
+        let first = week_containing(date("2025-08-13"), Weekday::Mon).unwrap();
+        let lines = week_grid(first, &spans, 90);
+
+        assert_eq!(
+            lines[0],
+            "       Mon 08/11   Tue 08/12   Wed 08/13   Thu 08/14   Fri 08/15   Sat 08/16   Sun 08/17"
+        );
+        assert_eq!(
+            lines[1],
+            format!("{}[Offsite{}]", " ".repeat(43), "=".repeat(14))
+        );
+        assert_eq!(lines[2], format!("07:00 {} 07:00 Re…+1", " ".repeat(12)));
+        assert_eq!(lines[3], format!("08:00 {} |", " ".repeat(12)));
+        assert_eq!(lines[4], format!("09:00 {} |", " ".repeat(12)));
+        assert_eq!(lines.last().unwrap(), "17:00");
+    }
+
+    #[test]
+    fn fit_truncates_by_display_width() {
+        assert_eq!(fit("会議", 6, '='), "会議==");
+        assert_eq!(fit("定例会議", 6, ' '), "定例… ");
+        assert_eq!(fit("abc", 0, ' '), "");
+    }
+}
@@ -0,0 +1,179 @@
+use anyhow::Result;
+use chrono::{DateTime, Days, Utc};
+use serde::Serialize;
+
+use crate::{
+    cli::{ConflictsCommand, ReportFormat},
+    storage::{Storage, StoredEvent},
+};
+
+use super::{
+    events::{DisplayZone, parse_timezone, parse_utc},
+    output::EventRecord,
+    range::{date_range, event_filter, resolve_range},
+};
+
+/// Days checked when no range is given, starting today.
+const DEFAULT_DAYS: u64 = 30;
+
+pub fn list_conflicts(storage: &Storage, cmd: ConflictsCommand) -> Result<()> {
+    let tz = parse_timezone(cmd.tz.as_deref())?;
+    let range = match resolve_range(&cmd.range, &tz)? {
+        Some(range) => range,
+        None => {
+            let today = tz.date_of(Utc::now());
+            date_range(today, today + Days::new(DEFAULT_DAYS), &tz)?
+        }
+    };
+    let events = storage.fetch_events(&event_filter(Some(range), &cmd.tags))?;
+    let conflicts = overlapping_pairs(&events)?;
+
+    match cmd.format {
+        ReportFormat::Json => {
+            let records = conflicts
+                .iter()
+                .map(|conflict| conflict.record(&tz))
+                .collect::<Result<Vec<_>>>()?;
+            println!("{}", serde_json::to_string_pretty(&records)?);
+        }
+        ReportFormat::Text if conflicts.is_empty() => println!("No overlapping events"),
+        ReportFormat::Text => {
+            for conflict in &conflicts {
+                println!(
+                    "#{} {}  <->  #{} {}",
+                    conflict.first.id,
+                    conflict.first.title,
+                    conflict.second.id,
+                    conflict.second.title
+                );
+                println!("  {}", conflict.describe(&tz));
+            }
+            println!("{} overlapping pair(s)", conflicts.len());
+        }
+    }
+    Ok(())
+}
+
+/// Two timed events and the span they share.
+struct Conflict<'a> {
+    first: &'a StoredEvent,
+    second: &'a StoredEvent,
+    starts: DateTime<Utc>,
+    ends: DateTime<Utc>,
+}
+
+#[derive(Serialize)]
+struct ConflictRecord {
+    first: EventRecord,
+    second: EventRecord,
+    overlap_start: String,
+    overlap_end: String,
+}
+
+impl Conflict<'_> {
+    fn record(&self, zone: &DisplayZone) -> Result<ConflictRecord> {
+        Ok(ConflictRecord {
+            first: EventRecord::new(self.first, zone)?,
+            second: EventRecord::new(self.second, zone)?,
+            overlap_start: self.starts.to_rfc3339(),
+            overlap_end: self.ends.to_rfc3339(),
+        })
+    }
+
+    fn describe(&self, zone: &DisplayZone) -> String {
+        let end_spec = if zone.date_of(self.starts) == zone.date_of(self.ends) {
+            "%H:%M"
+        } else {
+            "%Y-%m-%d %H:%M"
+        };
+        format!(
+            "{} -> {} {} ({} min)",
+            zone.format(self.starts, "%Y-%m-%d %H:%M"),
+            zone.format(self.ends, end_spec),
+            zone.name(),
+            (self.ends - self.starts).num_minutes()
+        )
+    }
+}
+
+/// Every pair of timed events that overlap, ordered by when the overlap
+/// begins. Occurrences of one series are not reported against each other.
+fn overlapping_pairs(events: &[StoredEvent]) -> Result<Vec<Conflict<'_>>> {
+    let mut timed = events
+        .iter()
+        .filter(|event| !event.all_day && !event.is_cancelled())
+        .map(|event| {
+            Ok((
+                parse_utc(&event.starts_at)?,
+                parse_utc(&event.ends_at)?,
+                event,
+            ))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    timed.sort_by_key(|(start, end, event)| (*start, *end, event.id));
+
+    let mut conflicts = Vec::new();
+    for (index, (start, end, event)) in timed.iter().enumerate() {
+        for (other_start, other_end, other) in &timed[index + 1..] {
+            if other_start >= end {
+                break;
+            }
+            if other_end > start && other.id != event.id {
+                conflicts.push(Conflict {
+                    first: event,
+                    second: other,
+                    starts: *other_start,
+                    ends: (*end).min(*other_end),
+                });
+            }
+        }
+    }
+    conflicts.sort_by_key(|conflict| (conflict.starts, conflict.first.id, conflict.second.id));
+    Ok(conflicts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::EventStatus;
+
+    fn event(id: i64, start: &str, end: &str) -> StoredEvent {
+        StoredEvent {
+            id,
+            title: format!("Event {id}"),
+            starts_at: format!("2025-08-11T{start}:00+00:00"),
+            ends_at: format!("2025-08-11T{end}:00+00:00"),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn finds_each_overlapping_pair_once() {
+        let mut all_day = event(4, "00:00", "23:59");
+        all_day.all_day = true;
+        let mut cancelled = event(6, "09:00", "10:00");
+        cancelled.status = Some(EventStatus::Cancelled);
+        let events = vec![
+            event(1, "09:00", "12:00"),
+            event(2, "09:30", "10:00"),
+            event(3, "11:30", "13:00"),
+            all_day,
+            // Back to back with #3: not a conflict.
+            event(5, "13:00", "14:00"),
+            // Cancelled, so it frees its time.
+            cancelled,
+        ];
+        let pairs: Vec<_> = overlapping_pairs(&events)
+            .unwrap()
+            .iter()
+            .map(|conflict| {
+                (
+                    conflict.first.id,
+                    conflict.second.id,
+                    (conflict.ends - conflict.starts).num_minutes(),
+                )
+            })
+            .collect();
+        assert_eq!(pairs, vec![(1, 2, 30), (1, 3, 30)]);
+    }
+}
@@ -0,0 +1,433 @@
+use std::{env, fs, path::Path, process::Command, time::Duration as StdDuration};
+
+use anyhow::{Context, Result, anyhow};
+use chrono::{Duration, Local};
+use humantime::format_duration;
+use serde::{Deserialize, Serialize};
+use tempfile::Builder;
+
+use crate::{
+    cli::EditCommand,
+    storage::{EventStatus, EventUpdate, Storage, StoredEvent},
+};
+
+use super::events::{
+    describe_reminder, parse_reminder, parse_reminders, parse_utc, resolve_explicit_timing,
+};
+
+const ERROR_PREFIX: &str = "# error: ";
+
+pub fn edit_event(storage: &mut Storage, cmd: EditCommand) -> Result<()> {
+    if cmd.interactive {
+        return edit_interactively(storage, cmd.id);
+    }
+
+    let update = EventUpdate {
+        title: cmd.title,
+        note: cmd.note,
+        append_note: cmd.append_note,
+        add_tags: cmd.add_tags,
+        remove_tags: cmd.remove_tags,
+        clear_tags: cmd.clear_tags,
+        private: (cmd.private || cmd.public).then_some(cmd.private),
+        location: cmd.location,
+        url: cmd.url,
+        status: cmd.status.as_deref().map(parse_status).transpose()?,
+        add_alarms: parse_reminders(&cmd.reminders)?,
+        clear_alarms: cmd.clear_reminders,
+        ..EventUpdate::default()
+    };
+    if update.is_empty() {
+        return Err(anyhow!(
+            "provide --title/--note/--append-note/--add-tag/--remove-tag/--clear-tags/\
+             --private/--public/--location/--url/--status/--remind/--clear-reminders \
+             or --interactive to edit an event"
+        ));
+    }
+    if !storage.update_event(cmd.id, &update)? {
+        return Err(anyhow!("No event found with id {}", cmd.id));
+    }
+    print_updated(storage, cmd.id)
+}
+
+fn print_updated(storage: &Storage, id: i64) -> Result<()> {
+    let event = storage
+        .fetch_event_by_id(id)?
+        .ok_or_else(|| anyhow!("No event found with id {id}"))?;
+    println!("Updated event #{} {}", event.id, event.title);
+    if !event.tags.is_empty() {
+        println!("  tags: {}", event.tags.join(", "));
+    }
+    if let Some(location) = &event.location {
+        println!("  location: {location}");
+    }
+    if let Some(url) = &event.url {
+        println!("  url: {url}");
+    }
+    if let Some(status) = event.status {
+        println!("  status: {status}");
+    }
+    if !event.alarms.is_empty() {
+        let reminders: Vec<_> = event.alarms.iter().map(|m| describe_reminder(*m)).collect();
+        println!("  reminders: {}", reminders.join(", "));
+    }
+    if event.private {
+        println!("  private");
+    }
+    Ok(())
+}
+
+/// A status given on the command line or in the editor; empty clears it.
+fn parse_status(value: &str) -> Result<Option<EventStatus>> {
+    let value = value.trim();
+    if value.is_empty() {
+        Ok(None)
+    } else {
+        value.parse().map(Some)
+    }
+}
+
+/// The editable view of an event written to the editor buffer.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct EventDocument {
+    title: String,
+    all_day: bool,
+    start: String,
+    end: String,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    private: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    location: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    url: Option<String>,
+    /// tentative, confirmed or cancelled.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    status: Option<String>,
+    /// Offsets before the start such as "10m" or "1day"; "-5m" is after.
+    #[serde(default)]
+    reminders: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    repeat: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    uid: Option<String>,
+    #[serde(default)]
+    note: String,
+}
+
+impl EventDocument {
+    fn from_event(event: &StoredEvent) -> Result<Self> {
+        let start = parse_utc(&event.starts_at)?;
+        let end = parse_utc(&event.ends_at)?;
+        let (start, end) = if event.all_day {
+            // All-day ends are stored exclusive; show the last day like `add --end`.
+            let last_day = (end - Duration::days(1)).max(start);
+            (
+                start.format("%Y-%m-%d").to_string(),
+                last_day.format("%Y-%m-%d").to_string(),
+            )
+        } else {
+            (
+                start.with_timezone(&Local).to_rfc3339(),
+                end.with_timezone(&Local).to_rfc3339(),
+            )
+        };
+        Ok(Self {
+            title: event.title.clone(),
+            all_day: event.all_day,
+            start,
+            end,
+            tags: event.tags.clone(),
+            private: event.private,
+            location: event.location.clone(),
+            url: event.url.clone(),
+            status: event.status.map(|status| status.to_string()),
+            reminders: event.alarms.iter().map(|m| format_reminder(*m)).collect(),
+            repeat: event.recurrence.as_ref().map(|rule| rule.to_string()),
+            uid: event.uid.clone(),
+            note: event.note.clone(),
+        })
+    }
+
+    fn render(&self, id: i64) -> Result<String> {
+        let body = toml::to_string(self).context("failed to serialize event")?;
+        Ok(format!(
+            "# Editing event #{id}. Save and quit to apply; lines starting with '#' are ignored.\n\
+             # Timed events take RFC3339 instants; all-day events take YYYY-MM-DD with an inclusive end.\n\
+             # Optional: location, url, status (tentative, confirmed or cancelled) and\n\
+             # reminders (\"10m\" before the start, \"-5m\" after).\n\
+             # uid and repeat are read-only; to change how a series repeats, add it again.\n\
+             {body}"
+        ))
+    }
+}
+
+fn edit_interactively(storage: &mut Storage, id: i64) -> Result<()> {
+    let event = storage
+        .fetch_event_by_id(id)?
+        .ok_or_else(|| anyhow!("No event found with id {id}"))?;
+    let original = EventDocument::from_event(&event)?;
+    // A fresh file only we can read, so nothing already planted in the
+    // temp directory is followed or reused.
+    let file = Builder::new()
+        .prefix(&format!("toki-note-event-{id}-"))
+        .suffix(".toml")
+        .tempfile()
+        .context("failed to create the edit buffer")?;
+    let buffer = original.render(id)?;
+
+    // Whatever goes wrong before the edit is stored, the buffer stays
+    // behind so nothing typed is lost.
+    let stored = edit_until_valid(file.path(), buffer, &event, &original).and_then(|update| {
+        if !update.is_empty() && !storage.update_event(id, &update)? {
+            return Err(anyhow!("No event found with id {id}"));
+        }
+        Ok(!update.is_empty())
+    });
+    let changed = match stored {
+        Ok(changed) => changed,
+        Err(err) => {
+            let kept = file
+                .into_temp_path()
+                .keep()
+                .context("failed to keep the edit buffer")?;
+            return Err(err.context(format!("your edits are kept in {}", kept.display())));
+        }
+    };
+    drop(file);
+
+    if !changed {
+        println!("No changes to event #{id}");
+        return Ok(());
+    }
+    print_updated(storage, id)
+}
+
+/// Opens `buffer` in the editor until it yields a valid update, putting the
+/// error at the top of the buffer after each failed attempt.
+fn edit_until_valid(
+    path: &Path,
+    mut buffer: String,
+    event: &StoredEvent,
+    original: &EventDocument,
+) -> Result<EventUpdate> {
+    loop {
+        fs::write(path, &buffer).with_context(|| format!("failed to write {}", path.display()))?;
+        run_editor(path)?;
+        let edited = fs::read_to_string(path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+
+        match build_update(&edited, event, original) {
+            Ok(update) => return Ok(update),
+            // Saving the buffer untouched after an error means the user gave up.
+            Err(err) if edited == buffer => return Err(err.context("edit abandoned")),
+            Err(err) => buffer = with_error_header(&edited, &err),
+        }
+    }
+}
+
+fn run_editor(path: &Path) -> Result<()> {
+    let editor = env::var("VISUAL")
+        .or_else(|_| env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".to_string());
+    // Split rather than hand to a shell, so values like "code --wait" work
+    // without needing one.
+    let words = shlex::split(&editor).unwrap_or_default();
+    let (program, args) = words
+        .split_first()
+        .ok_or_else(|| anyhow!("cannot parse editor command '{editor}'"))?;
+    let status = Command::new(program)
+        .args(args)
+        .arg(path)
+        .status()
+        .with_context(|| format!("failed to launch editor '{editor}'"))?;
+    if !status.success() {
+        return Err(anyhow!("editor '{editor}' exited with {status}"));
+    }
+    Ok(())
+}
+
+/// A reminder offset as written in the edit buffer; negative ones fire
+/// after the start and carry a leading `-`.
+fn format_reminder(minutes: i64) -> String {
+    let span = format_duration(StdDuration::from_secs(minutes.unsigned_abs() * 60));
+    if minutes < 0 {
+        format!("-{span}")
+    } else {
+        span.to_string()
+    }
+}
+
+fn parse_document_reminder(value: &str) -> Result<i64> {
+    match value.trim().strip_prefix('-') {
+        Some(after) => parse_reminder(after).map(|minutes| -minutes),
+        None => parse_reminder(value),
+    }
+}
+
+fn with_error_header(text: &str, err: &anyhow::Error) -> String {
+    let mut out = String::new();
+    for line in format!("{err:#}").lines() {
+        out.push_str(ERROR_PREFIX);
+        out.push_str(line);
+        out.push('\n');
+    }
+    for line in text.lines().filter(|line| !line.starts_with(ERROR_PREFIX)) {
+        out.push_str(line);
+        out.push('\n');
+    }
+    out
+}
+
+/// Parses the edited buffer and turns it into the changes to apply, using the
+/// same timing validation as `add`.
+fn build_update(text: &str, event: &StoredEvent, original: &EventDocument) -> Result<EventUpdate> {
+    let edited: EventDocument = toml::from_str(text).context("invalid TOML")?;
+    if edited.uid != original.uid {
+        return Err(anyhow!("uid is read-only"));
+    }
+    if edited.repeat != original.repeat {
+        return Err(anyhow!("repeat is read-only"));
+    }
+    let title = edited.title.trim();
+    if title.is_empty() {
+        return Err(anyhow!("title must not be empty"));
+    }
+
+    let mut update = EventUpdate::default();
+    if title != event.title {
+        update.title = Some(title.to_string());
+    }
+    if edited.note != event.note {
+        update.note = Some(edited.note.clone());
+    }
+    if edited.private != event.private {
+        update.private = Some(edited.private);
+    }
+    let location = edited
+        .location
+        .as_deref()
+        .map(str::trim)
+        .unwrap_or_default();
+    if location != event.location.as_deref().unwrap_or_default() {
+        update.location = Some(location.to_string());
+    }
+    let url = edited.url.as_deref().map(str::trim).unwrap_or_default();
+    if url != event.url.as_deref().unwrap_or_default() {
+        update.url = Some(url.to_string());
+    }
+    let status = parse_status(edited.status.as_deref().unwrap_or_default())?;
+    if status != event.status {
+        update.status = Some(status);
+    }
+    let mut alarms = edited
+        .reminders
+        .iter()
+        .map(|value| parse_document_reminder(value))
+        .collect::<Result<Vec<_>>>()?;
+    alarms.sort();
+    alarms.dedup();
+    let mut current = event.alarms.clone();
+    current.sort();
+    if alarms != current {
+        update.clear_alarms = true;
+        update.add_alarms = alarms;
+    }
+
+    let timing_changed = edited.all_day != original.all_day
+        || edited.start != original.start
+        || edited.end != original.end;
+    if timing_changed {
+        let (starts_at, ends_at) =
+            resolve_explicit_timing(&edited.start, &edited.end, edited.all_day)?;
+        update.starts_at = Some(starts_at);
+        update.ends_at = Some(ends_at);
+        update.all_day = Some(edited.all_day);
+    }
+
+    let mut tags: Vec<String> = edited
+        .tags
+        .iter()
+        .map(|tag| tag.trim().to_lowercase())
+        .filter(|tag| !tag.is_empty())
+        .collect();
+    tags.sort();
+    tags.dedup();
+    if tags != event.tags {
+        update.remove_tags = event
+            .tags
+            .iter()
+            .filter(|tag| !tags.contains(tag))
+            .cloned()
+            .collect();
+        update.add_tags = tags
+            .into_iter()
+            .filter(|tag| !event.tags.contains(tag))
+            .collect();
+    }
+    Ok(update)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> StoredEvent {
+        StoredEvent {
+            id: 7,
+            title: "Review".into(),
+            starts_at: "2025-03-01T00:00:00+00:00".into(),
+            ends_at: "2025-03-03T00:00:00+00:00".into(),
+            all_day: true,
+            tags: vec!["work".into()],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn untouched_document_produces_no_update() {
+        let event = sample();
+        let original = EventDocument::from_event(&event).unwrap();
+        assert_eq!(original.end, "2025-03-02");
+        let update = build_update(&original.render(7).unwrap(), &event, &original).unwrap();
+        assert!(update.is_empty());
+    }
+
+    #[test]
+    fn edits_are_validated_and_diffed() {
+        let event = sample();
+        let original = EventDocument::from_event(&event).unwrap();
+        let text = original
+            .render(7)
+            .unwrap()
+            .replace("\"Review\"", "\"Design review\"")
+            .replace("[\"work\"]", "[\"Work\", \"design\"]");
+        let update = build_update(&text, &event, &original).unwrap();
+        assert_eq!(update.title.as_deref(), Some("Design review"));
+        assert_eq!(update.add_tags, vec!["design"]);
+        assert!(update.remove_tags.is_empty());
+        assert!(update.starts_at.is_none());
+        assert!(!update.clear_alarms);
+
+        let text = original
+            .render(7)
+            .unwrap()
+            .replace("reminders = []", "reminders = [\"1day\", \"-5m\"]");
+        let update = build_update(&text, &event, &original).unwrap();
+        assert!(update.clear_alarms);
+        assert_eq!(update.add_alarms, vec![-5, 1440]);
+        let repeating = format!("repeat = \"FREQ=DAILY\"\n{}", original.render(7).unwrap());
+        assert!(build_update(&repeating, &event, &original).is_err());
+
+        let backwards = original
+            .render(7)
+            .unwrap()
+            .replace("end = \"2025-03-02\"", "end = \"2025-02-01\"");
+        assert!(build_update(&backwards, &event, &original).is_err());
+        let err = build_update("title = ", &event, &original).unwrap_err();
+        let annotated = with_error_header("title = \n", &err);
+        assert!(annotated.starts_with(ERROR_PREFIX));
+    }
+}
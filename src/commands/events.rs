@@ -1,14 +1,31 @@
+use std::{env, fs};
+
 use anyhow::{Context, Result, anyhow};
-use chrono::{DateTime, Days, Duration, Local, LocalResult, NaiveDate, NaiveTime, TimeZone, Utc};
+use chrono::{
+    DateTime, Days, Duration, Local, LocalResult, NaiveDate, NaiveDateTime, NaiveTime, TimeZone,
+    Utc, Weekday,
+};
 use chrono_tz::Tz;
-use humantime::parse_duration;
+use humantime::{format_duration, parse_duration};
+use serde::Serialize;
 
 use crate::{
-    cli::{AddCommand, DeleteCommand, ListCommand, MoveCommand},
-    storage::{NewEvent, Storage, StoredEvent},
+    cli::{
+        AddCommand, DeleteCommand, ListCommand, ListFormat, MoveCommand, OverlapArgs, ReportFormat,
+    },
+    recurrence::{Recurrence, parse_by_day, parse_frequency},
+    storage::{EventFilter, NewEvent, Storage, StoredEvent},
+};
+
+use super::{
+    output::{EventRecord, report, write_events},
+    range::{event_filter, resolve_range},
+    template::{DEFAULT_LIST_TEMPLATE, Template},
 };
 
 pub fn add_event(storage: &mut Storage, cmd: AddCommand) -> Result<()> {
+    let status = cmd.status.as_deref().map(str::parse).transpose()?;
+    let alarms = parse_reminders(&cmd.reminders)?;
     let timing_args = TimingArgs::from_add(&cmd);
     let timing = if cmd.all_day {
         if cmd.duration.is_some() {
@@ -18,6 +35,14 @@ pub fn add_event(storage: &mut Storage, cmd: AddCommand) -> Result<()> {
     } else {
         parse_timed_range(&timing_args, Duration::minutes(30))?
     };
+    let recurrence = parse_recurrence(&cmd)?;
+    check_overlaps(storage, &cmd.overlap, &timing, cmd.all_day, None)?;
+    // Series keep the zone they were written in, so exports carry a TZID
+    // that puts BYDAY on the local weekday and follows DST.
+    let tzid = recurrence
+        .as_ref()
+        .and_then(|_| system_zone())
+        .map(|zone| zone.name().to_string());
 
     let new_event = NewEvent {
         title: cmd.title,
@@ -26,79 +51,221 @@ pub fn add_event(storage: &mut Storage, cmd: AddCommand) -> Result<()> {
         ends_at: timing.ends_at,
         all_day: cmd.all_day,
         tags: cmd.tags,
+        private: cmd.private,
+        location: cmd.location.filter(|location| !location.is_empty()),
+        url: cmd.url.filter(|url| !url.is_empty()),
+        status,
+        alarms,
         uid: None,
+        recurrence,
+        tzid,
+        ..NewEvent::default()
     };
 
     let row_id = storage.insert_event(new_event)?;
-    println!("Stored event #{row_id}");
-    Ok(())
+    report_event(
+        storage,
+        cmd.format,
+        "added",
+        row_id,
+        format!("Stored event #{row_id}"),
+    )
+}
+
+/// JSON outcome of `add` and `move`: the event as stored afterwards.
+#[derive(Serialize)]
+struct EventReport {
+    action: &'static str,
+    event: EventRecord,
+}
+
+fn report_event(
+    storage: &Storage,
+    format: ReportFormat,
+    action: &'static str,
+    id: i64,
+    message: String,
+) -> Result<()> {
+    let event = storage
+        .fetch_event_by_id(id)?
+        .ok_or_else(|| anyhow!("No event found with id {id}"))?;
+    let event = EventRecord::new(&event, &DisplayZone::Local)?;
+    report(format, message, EventReport { action, event })
 }
 
 pub fn list_events(storage: &Storage, cmd: ListCommand) -> Result<()> {
-    let range = if let Some(day) = cmd.day {
-        Some(day_range(&day)?)
-    } else {
-        None
-    };
-    let events = storage.fetch_events(range)?;
     let tz = parse_timezone(cmd.tz.as_deref())?;
+    let template = Template::parse(cmd.template.as_deref().unwrap_or(DEFAULT_LIST_TEMPLATE))
+        .context("invalid --template")?;
+    let range = resolve_range(&cmd.range, &tz)?;
+    let filter = EventFilter {
+        query: cmd.query,
+        ..event_filter(range, &cmd.tags)
+    };
+    let events = storage.fetch_events(&filter)?;
+    if !matches!(cmd.format, ListFormat::Text) {
+        return write_events(&events, cmd.format, &tz);
+    }
 
     if events.is_empty() {
         println!("No events found");
         return Ok(());
     }
-
     for event in events {
-        let timing = format_event_timing(&event, &tz)?;
-        println!("#{} {}", event.id, event.title);
-        println!("  {timing}");
-        if !event.tags.is_empty() {
-            println!("  tags: {}", event.tags.join(", "));
-        }
-        if !event.note.is_empty() {
-            println!("  note: {}", event.note);
-        }
-        println!();
+        println!("{}", template.render(&event, &tz)?);
     }
 
     Ok(())
 }
 
 pub fn delete_event(storage: &mut Storage, cmd: DeleteCommand) -> Result<()> {
+    if let Some(date) = cmd.occurrence.as_deref() {
+        return delete_occurrence(storage, &cmd, date);
+    }
+    if !cmd.tags.is_empty() {
+        return delete_tagged(storage, &cmd);
+    }
     match (cmd.id, cmd.title.as_deref()) {
         (Some(id), None) => {
             let removed = storage.delete_by_id(id)?;
-            if removed {
-                println!("Deleted event #{id}");
+            let message = if removed {
+                format!("Deleted event #{id}")
             } else {
-                println!("No event found with id {id}");
-            }
+                format!("No event found with id {id}")
+            };
+            report(cmd.format, message, deleted(removed.then_some(id)))?;
         }
         (None, Some(title)) => {
+            let ids = title_ids(storage, title)?;
             let removed = storage.delete_by_title(title)?;
-            if removed > 0 {
-                println!("Deleted {removed} event(s) titled '{title}'");
+            let message = if removed > 0 {
+                format!("Deleted {removed} event(s) titled '{title}'")
             } else {
-                println!("No events found titled '{title}'");
-            }
+                format!("No events found titled '{title}'")
+            };
+            report(cmd.format, message, deleted(ids))?;
         }
         (Some(id), Some(title)) => {
-            let removed = storage.delete_by_id(id)?;
-            if removed {
-                println!("Deleted event #{id} titled '{title}'");
+            if storage.delete_by_id(id)? {
+                report(
+                    cmd.format,
+                    format!("Deleted event #{id} titled '{title}'"),
+                    deleted([id]),
+                )?;
             } else {
-                println!("No event #{id}; attempting title deletion");
+                let ids = title_ids(storage, title)?;
                 let removed = storage.delete_by_title(title)?;
-                println!("Deleted {removed} event(s) titled '{title}'");
+                report(
+                    cmd.format,
+                    format!(
+                        "No event #{id}; attempting title deletion\n\
+                         Deleted {removed} event(s) titled '{title}'"
+                    ),
+                    deleted(ids),
+                )?;
             }
         }
-        (None, None) => return Err(anyhow!("Provide either --id or --title")),
+        (None, None) => return Err(anyhow!("Provide --id, --title or --tag")),
     }
     Ok(())
 }
 
+fn title_ids(storage: &Storage, title: &str) -> Result<Vec<i64>> {
+    Ok(storage
+        .fetch_events_by_title(title)?
+        .into_iter()
+        .map(|event| event.id)
+        .collect())
+}
+
+/// JSON outcome of `delete`. `action` is `deleted` (whole events, `ids`),
+/// `cancelled` (one occurrence of series `ids[0]`) or `truncated` (series
+/// `ids[0]` now ends before `occurrence`).
+#[derive(Serialize)]
+struct DeleteReport {
+    action: &'static str,
+    ids: Vec<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    occurrence: Option<String>,
+}
+
+fn deleted(ids: impl IntoIterator<Item = i64>) -> DeleteReport {
+    DeleteReport {
+        action: "deleted",
+        ids: ids.into_iter().collect(),
+        occurrence: None,
+    }
+}
+
+fn delete_tagged(storage: &mut Storage, cmd: &DeleteCommand) -> Result<()> {
+    if cmd.tags.tags.is_empty() {
+        return Err(anyhow!(
+            "--exclude-tag only narrows a delete; add --tag to choose what to remove"
+        ));
+    }
+    let ids = storage.delete_matching(&event_filter(None, &cmd.tags))?;
+    let message = if ids.is_empty() {
+        "No events match the tag filter".to_string()
+    } else {
+        format!("Deleted {} event(s) matching the tag filter", ids.len())
+    };
+    report(cmd.format, message, deleted(ids))
+}
+
+fn delete_occurrence(storage: &mut Storage, cmd: &DeleteCommand, date: &str) -> Result<()> {
+    let event = resolve_single_event(storage, cmd.id, cmd.title.as_deref(), "delete")?;
+    let original = find_occurrence(&event, date)?;
+
+    if !cmd.this_and_following {
+        storage.add_exdate(event.id, &original.to_rfc3339())?;
+        return report(
+            cmd.format,
+            format!("Cancelled the {date} occurrence of event #{}", event.id),
+            DeleteReport {
+                action: "cancelled",
+                ids: vec![event.id],
+                occurrence: Some(original.to_rfc3339()),
+            },
+        );
+    }
+
+    if original <= parse_utc(&event.starts_at)? {
+        storage.delete_by_id(event.id)?;
+        report(
+            cmd.format,
+            format!("Deleted event #{} (every occurrence)", event.id),
+            deleted([event.id]),
+        )
+    } else {
+        let (truncated, _) = split_rule(&event, original)?;
+        storage.split_series(event.id, &original.to_rfc3339(), &truncated, None)?;
+        report(
+            cmd.format,
+            format!("Ended event #{} before {date}", event.id),
+            DeleteReport {
+                action: "truncated",
+                ids: vec![event.id],
+                occurrence: Some(original.to_rfc3339()),
+            },
+        )
+    }
+}
+
 pub fn move_event(storage: &mut Storage, cmd: MoveCommand) -> Result<()> {
-    let mut event = resolve_move_target(storage, &cmd)?;
+    let mut event = resolve_single_event(storage, cmd.id, cmd.title.as_deref(), "move")?;
+    let occurrence = cmd
+        .occurrence
+        .as_deref()
+        .map(|date| find_occurrence(&event, date))
+        .transpose()?;
+    if let Some(original) = occurrence {
+        // Time the occurrence as if it were the event being moved, so
+        // --time/--duration adjust that instance rather than the series start.
+        let span = parse_utc(&event.ends_at)?.signed_duration_since(parse_utc(&event.starts_at)?);
+        event.starts_at = original.to_rfc3339();
+        event.ends_at = (original + span).to_rfc3339();
+    }
+
     let timing_args = TimingArgs::from_move(&cmd, &event)?;
     if !timing_args.has_explicit_input() {
         return Err(anyhow!(
@@ -126,20 +293,260 @@ pub fn move_event(storage: &mut Storage, cmd: MoveCommand) -> Result<()> {
         }
         parse_timed_range(&timing_args, duration)?
     };
+    check_overlaps(
+        storage,
+        &cmd.overlap,
+        &timing,
+        event.all_day,
+        Some(event.id),
+    )?;
 
-    if !storage.update_event_timing(event.id, &timing.starts_at, &timing.ends_at, event.all_day)? {
-        return Err(anyhow!("failed to update event #{}", event.id));
-    }
+    let moved_id = match occurrence {
+        None => {
+            if !storage.update_event_timing(
+                event.id,
+                &timing.starts_at,
+                &timing.ends_at,
+                event.all_day,
+            )? {
+                return Err(anyhow!("failed to update event #{}", event.id));
+            }
+            event.id
+        }
+        Some(original) if cmd.this_and_following => {
+            move_following(storage, &event, original, &timing)?
+        }
+        Some(original) => storage.upsert_occurrence_override(
+            event.id,
+            &original.to_rfc3339(),
+            occurrence_copy(&event, &timing),
+        )?,
+    };
 
     event.starts_at = timing.starts_at;
     event.ends_at = timing.ends_at;
     let summary = format_event_timing(&event, &DisplayZone::Local)?;
-    println!("Moved event #{} {}", event.id, summary);
+    let message = if moved_id == event.id {
+        format!("Moved event #{} {}", event.id, summary)
+    } else {
+        format!("Moved event #{} as #{moved_id} {}", event.id, summary)
+    };
+    report_event(storage, cmd.format, "moved", moved_id, message)
+}
+
+/// Warns about timed events overlapping `timing`, or refuses with
+/// `--strict`. Only the first occurrence of a new series is checked, and
+/// all-day events never count as double-bookings.
+fn check_overlaps(
+    storage: &Storage,
+    args: &OverlapArgs,
+    timing: &EventTiming,
+    all_day: bool,
+    ignore: Option<i64>,
+) -> Result<()> {
+    if all_day || args.allow_overlap {
+        return Ok(());
+    }
+    let overlaps = storage
+        .overlapping_events(&timing.starts_at, &timing.ends_at, ignore)?
+        .iter()
+        .map(|event| {
+            Ok(format!(
+                "#{} {} ({})",
+                event.id,
+                event.title,
+                format_event_timing(event, &DisplayZone::Local)?
+            ))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    if overlaps.is_empty() {
+        return Ok(());
+    }
+    if args.strict {
+        return Err(anyhow!(
+            "refusing to double-book; overlaps {} (drop --strict to store it anyway)",
+            overlaps.join(", ")
+        ));
+    }
+    for overlap in overlaps {
+        eprintln!("warning: overlaps {overlap}");
+    }
     Ok(())
 }
 
-fn resolve_move_target(storage: &Storage, cmd: &MoveCommand) -> Result<StoredEvent> {
-    match (cmd.id, cmd.title.as_deref()) {
+/// Splits the series at `original`: the old row keeps earlier occurrences
+/// and a new series starting at `timing` takes over the rest. Returns the id
+/// of the row now holding the moved occurrence.
+fn move_following(
+    storage: &mut Storage,
+    event: &StoredEvent,
+    original: DateTime<Utc>,
+    timing: &EventTiming,
+) -> Result<i64> {
+    let series_start = storage
+        .fetch_event_by_id(event.id)?
+        .ok_or_else(|| anyhow!("No event found with id {}", event.id))?;
+    if original <= parse_utc(&series_start.starts_at)? {
+        storage.update_event_timing(event.id, &timing.starts_at, &timing.ends_at, event.all_day)?;
+        return Ok(event.id);
+    }
+
+    let (truncated, mut continued) = split_rule(&series_start, original)?;
+    // Keep BYDAY in step when the move lands on a different weekday.
+    let zone = series_zone(event);
+    let shift = zone
+        .date_of(parse_utc(&timing.starts_at)?)
+        .signed_duration_since(zone.date_of(original))
+        .num_days();
+    if shift.rem_euclid(7) != 0 {
+        // "The second Tuesday" moved a day later is not "the second
+        // Wednesday", so numbered weekdays cannot simply be shifted.
+        if continued.by_day.iter().any(|day| day.ordinal.is_some()) {
+            return Err(anyhow!(
+                "cannot move the rest of event #{} to another weekday: it repeats on \
+                 numbered weekdays ({continued}); move single occurrences instead",
+                event.id
+            ));
+        }
+        for day in &mut continued.by_day {
+            day.weekday = shift_weekday(day.weekday, shift);
+        }
+    }
+
+    let mut new_series = occurrence_copy(event, timing);
+    new_series.recurrence = Some(continued);
+    let new_id = storage.split_series(
+        event.id,
+        &original.to_rfc3339(),
+        &truncated,
+        Some(new_series),
+    )?;
+    new_id.ok_or_else(|| anyhow!("failed to split event #{}", event.id))
+}
+
+/// Rules for the part of the series before `original` and the part from it on.
+fn split_rule(event: &StoredEvent, original: DateTime<Utc>) -> Result<(Recurrence, Recurrence)> {
+    let rule = event
+        .recurrence
+        .clone()
+        .ok_or_else(|| anyhow!("event #{} does not repeat", event.id))?;
+    let mut truncated = rule.clone();
+    let mut continued = rule;
+    if let Some(count) = continued.count {
+        let before = event.occurrences_before(original)?;
+        truncated.count = Some(before);
+        continued.count = Some(count.saturating_sub(before));
+    } else {
+        truncated.until = Some(original - Duration::seconds(1));
+    }
+    Ok((truncated, continued))
+}
+
+fn shift_weekday(weekday: Weekday, days: i64) -> Weekday {
+    let offset = days.rem_euclid(7) as u32;
+    (0..offset).fold(weekday, |day, _| day.succ())
+}
+
+pub(super) fn occurrence_copy(event: &StoredEvent, timing: &EventTiming) -> NewEvent {
+    NewEvent {
+        title: event.title.clone(),
+        note: event.note.clone(),
+        starts_at: timing.starts_at.clone(),
+        ends_at: timing.ends_at.clone(),
+        all_day: event.all_day,
+        tags: event.tags.clone(),
+        tzid: event.tzid.clone(),
+        private: event.private,
+        location: event.location.clone(),
+        url: event.url.clone(),
+        status: event.status,
+        alarms: event.alarms.clone(),
+        ..NewEvent::default()
+    }
+}
+
+/// Original start of the occurrence of series `event` on `input` (a date in
+/// the series' own zone for timed series, the stored date for all-day ones).
+fn find_occurrence(event: &StoredEvent, input: &str) -> Result<DateTime<Utc>> {
+    if event.recurrence.is_none() {
+        return Err(anyhow!(
+            "event #{} does not repeat; drop --occurrence",
+            event.id
+        ));
+    }
+    let date = parse_date(input)?;
+    let next = date.succ_opt().ok_or_else(|| anyhow!("date overflow"))?;
+    let (window_start, window_end) = if event.all_day {
+        (utc_midnight(date)?, utc_midnight(next)?)
+    } else {
+        let zone = series_zone(event);
+        (zone.midnight(date)?, zone.midnight(next)?)
+    };
+    event
+        .occurrence_starts(window_start, window_end)?
+        .into_iter()
+        .find(|start| *start >= window_start)
+        .ok_or_else(|| anyhow!("event #{} has no occurrence on {date}", event.id))
+}
+
+/// The zone timed series `event` repeats in: its `tzid`, or the system zone
+/// for series stored without one.
+fn series_zone(event: &StoredEvent) -> DisplayZone {
+    event
+        .tzid
+        .as_deref()
+        .and_then(|name| name.parse().ok())
+        .map_or(DisplayZone::Local, DisplayZone::Named)
+}
+
+pub(super) fn utc_midnight(date: NaiveDate) -> Result<DateTime<Utc>> {
+    Ok(date
+        .and_hms_opt(0, 0, 0)
+        .ok_or_else(|| anyhow!("invalid day"))?
+        .and_utc())
+}
+
+fn parse_recurrence(cmd: &AddCommand) -> Result<Option<Recurrence>> {
+    let Some(repeat) = cmd.repeat.as_deref() else {
+        return Ok(None);
+    };
+    let mut rule = Recurrence::new(parse_frequency(repeat)?);
+    if let Some(interval) = cmd.interval {
+        if interval == 0 {
+            return Err(anyhow!("--interval must be at least 1"));
+        }
+        rule.interval = interval;
+    }
+    rule.by_day = cmd
+        .by_day
+        .iter()
+        .map(|value| parse_by_day(value))
+        .collect::<Result<Vec<_>>>()?;
+    rule.count = cmd.count;
+    if let Some(until) = cmd.until.as_deref() {
+        let last_moment = parse_date(until)?
+            .and_hms_opt(23, 59, 59)
+            .ok_or_else(|| anyhow!("invalid --until date"))?;
+        rule.until = Some(if cmd.all_day {
+            last_moment.and_utc()
+        } else {
+            Local
+                .from_local_datetime(&last_moment)
+                .latest()
+                .ok_or_else(|| anyhow!("--until '{until}' does not exist in the current timezone"))?
+                .with_timezone(&Utc)
+        });
+    }
+    Ok(Some(rule))
+}
+
+fn resolve_single_event(
+    storage: &Storage,
+    id: Option<i64>,
+    title: Option<&str>,
+    action: &str,
+) -> Result<StoredEvent> {
+    match (id, title) {
         (Some(id), _) => storage
             .fetch_event_by_id(id)?
             .ok_or_else(|| anyhow!("No event found with id {id}")),
@@ -149,7 +556,7 @@ fn resolve_move_target(storage: &Storage, cmd: &MoveCommand) -> Result<StoredEve
                 Err(anyhow!("No events found titled '{title}'"))
             } else if matches.len() > 1 {
                 Err(anyhow!(
-                    "Multiple events titled '{title}'. Use --id to specify which one to move."
+                    "Multiple events titled '{title}'. Use --id to specify which one to {action}."
                 ))
             } else {
                 Ok(matches.remove(0))
@@ -226,12 +633,56 @@ pub(super) fn parse_timezone(input: Option<&str>) -> Result<DisplayZone> {
     }
 }
 
+/// The IANA zone behind the system's local time, from `TZ` or the
+/// `/etc/localtime` link; `None` when it cannot be named.
+fn system_zone() -> Option<Tz> {
+    if let Ok(value) = env::var("TZ") {
+        return value.trim_start_matches(':').parse().ok();
+    }
+    if let Some(name) = fs::read_link("/etc/localtime")
+        .ok()
+        .and_then(|target| Some(target.to_str()?.split_once("zoneinfo/")?.1.to_string()))
+    {
+        return name.parse().ok();
+    }
+    fs::read_to_string("/etc/timezone")
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+}
+
 pub(super) fn parse_utc(value: &str) -> Result<DateTime<Utc>> {
     Ok(DateTime::parse_from_rfc3339(value)
         .with_context(|| format!("invalid timestamp '{value}'"))?
         .with_timezone(&Utc))
 }
 
+/// Validates an explicit start/end pair with the same rules as `add`,
+/// returning normalized UTC RFC3339 instants.
+pub(super) fn resolve_explicit_timing(
+    start: &str,
+    end: &str,
+    all_day: bool,
+) -> Result<(String, String)> {
+    let args = TimingArgs {
+        start: Some(start),
+        date: None,
+        time: None,
+        end: Some(end),
+        duration: None,
+        default_date: Local::now().date_naive(),
+        existing_start: None,
+        existing_end: None,
+    };
+    let timing = if all_day {
+        parse_all_day_range(&args, Duration::days(1))?
+    } else {
+        parse_timed_range(&args, Duration::minutes(30))?
+    };
+    Ok((timing.starts_at, timing.ends_at))
+}
+
 struct TimingArgs<'a> {
     start: Option<&'a str>,
     date: Option<&'a str>,
@@ -311,13 +762,17 @@ fn parse_all_day_range(args: &TimingArgs<'_>, default_span: Duration) -> Result<
             .ok_or_else(|| anyhow!("date overflow"))?
     };
 
+    if end_dt <= start_dt {
+        return Err(anyhow!("--end must not be earlier than --start"));
+    }
+
     Ok(EventTiming {
         starts_at: start_dt.to_rfc3339(),
         ends_at: end_dt.to_rfc3339(),
     })
 }
 
-fn parse_date(input: &str) -> Result<NaiveDate> {
+pub(super) fn parse_date(input: &str) -> Result<NaiveDate> {
     if let Ok(date) = NaiveDate::parse_from_str(input, "%Y-%m-%d") {
         return Ok(date);
     }
@@ -409,21 +864,6 @@ fn offset_date(base: NaiveDate, days: i64) -> Option<NaiveDate> {
     }
 }
 
-pub(super) fn day_range(day: &str) -> Result<(String, String)> {
-    let date = parse_date(day)?;
-    let start = date
-        .and_hms_opt(0, 0, 0)
-        .ok_or_else(|| anyhow!("invalid day"))?
-        .and_utc();
-    let end = date
-        .succ_opt()
-        .ok_or_else(|| anyhow!("date overflow"))?
-        .and_hms_opt(0, 0, 0)
-        .ok_or_else(|| anyhow!("invalid day"))?
-        .and_utc();
-    Ok((start.to_rfc3339(), end.to_rfc3339()))
-}
-
 fn parse_timed_range(args: &TimingArgs<'_>, default_duration: Duration) -> Result<EventTiming> {
     let start_dt = if let Some(start_value) = args.start {
         parse_explicit_instant(start_value)?
@@ -523,9 +963,33 @@ fn parse_time_of_day(value: &str) -> Result<NaiveTime> {
     ))
 }
 
-struct EventTiming {
-    starts_at: String,
-    ends_at: String,
+/// Reminder offsets such as `10m` or `1d`, as minutes before the start.
+pub(super) fn parse_reminders(values: &[String]) -> Result<Vec<i64>> {
+    values.iter().map(|value| parse_reminder(value)).collect()
+}
+
+pub(super) fn parse_reminder(value: &str) -> Result<i64> {
+    let span = parse_duration(value.trim())
+        .with_context(|| format!("failed to parse reminder '{value}'"))?;
+    if span.as_secs() % 60 != 0 || span.subsec_nanos() != 0 {
+        return Err(anyhow!("reminder '{value}' must be whole minutes"));
+    }
+    i64::try_from(span.as_secs() / 60).map_err(|_| anyhow!("reminder '{value}' is too long"))
+}
+
+/// `10m before`, `1day before` or `5m after` for a reminder offset.
+pub(super) fn describe_reminder(minutes: i64) -> String {
+    let span = format_duration(std::time::Duration::from_secs(minutes.unsigned_abs() * 60));
+    match minutes {
+        0 => "at start".to_string(),
+        _ if minutes > 0 => format!("{span} before"),
+        _ => format!("{span} after"),
+    }
+}
+
+pub(super) struct EventTiming {
+    pub(super) starts_at: String,
+    pub(super) ends_at: String,
 }
 
 pub(super) enum DisplayZone {
@@ -533,6 +997,70 @@ pub(super) enum DisplayZone {
     Named(Tz),
 }
 
+impl DisplayZone {
+    pub(super) fn name(&self) -> String {
+        match self {
+            DisplayZone::Local => Local::now().format("%Z").to_string(),
+            DisplayZone::Named(tz) => tz.name().to_string(),
+        }
+    }
+
+    /// Wall-clock time of `instant` in this zone.
+    pub(super) fn naive(&self, instant: DateTime<Utc>) -> NaiveDateTime {
+        match self {
+            DisplayZone::Local => instant.with_timezone(&Local).naive_local(),
+            DisplayZone::Named(tz) => instant.with_timezone(tz).naive_local(),
+        }
+    }
+
+    /// `instant` in this zone, formatted with strftime-style `spec`.
+    pub(super) fn format(&self, instant: DateTime<Utc>, spec: &str) -> String {
+        match self {
+            DisplayZone::Local => instant.with_timezone(&Local).format(spec).to_string(),
+            DisplayZone::Named(tz) => instant.with_timezone(tz).format(spec).to_string(),
+        }
+    }
+
+    pub(super) fn rfc3339(&self, instant: DateTime<Utc>) -> String {
+        match self {
+            DisplayZone::Local => instant.with_timezone(&Local).to_rfc3339(),
+            DisplayZone::Named(tz) => instant.with_timezone(tz).to_rfc3339(),
+        }
+    }
+
+    /// The instant `date` begins in this zone.
+    pub(super) fn midnight(&self, date: NaiveDate) -> Result<DateTime<Utc>> {
+        let naive = date
+            .and_hms_opt(0, 0, 0)
+            .ok_or_else(|| anyhow!("invalid day"))?;
+        self.instant(naive)
+            .ok_or_else(|| anyhow!("{date} has no midnight in the display timezone"))
+    }
+
+    /// The instant wall-clock time `naive` names in this zone (the earlier
+    /// one when clocks fall back), or `None` inside a gap.
+    pub(super) fn instant(&self, naive: NaiveDateTime) -> Option<DateTime<Utc>> {
+        match self {
+            DisplayZone::Local => Local
+                .from_local_datetime(&naive)
+                .earliest()
+                .map(|dt| dt.with_timezone(&Utc)),
+            DisplayZone::Named(tz) => tz
+                .from_local_datetime(&naive)
+                .earliest()
+                .map(|dt| dt.with_timezone(&Utc)),
+        }
+    }
+
+    /// The calendar date `instant` falls on in this zone.
+    pub(super) fn date_of(&self, instant: DateTime<Utc>) -> NaiveDate {
+        match self {
+            DisplayZone::Local => instant.with_timezone(&Local).date_naive(),
+            DisplayZone::Named(tz) => instant.with_timezone(tz).date_naive(),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,49 +1,75 @@
 use std::path::PathBuf;
 
 use anyhow::{Context, Result};
+use chrono::{DateTime, Days, Duration, Utc};
 use ics::{
-    Event as IcsEvent, ICalendar, parameters,
-    properties::{Description as IcsDescription, DtEnd, DtStart, Summary},
+    Alarm, Event as IcsEvent, FreeBusy, ICalendar, parameters,
+    properties::{
+        Class, Description as IcsDescription, DtEnd, DtStart, ExDate, FreeBusyTime, Location,
+        RRule, RecurrenceID, Status, Summary, Trigger, URL,
+    },
 };
 use rss::{ChannelBuilder, GuidBuilder, ItemBuilder};
 
 use crate::{
-    cli::{IcalCommand, RssCommand},
-    storage::Storage,
+    cli::{FreebusyCommand, IcalCommand, PrivateEvents, RssCommand},
+    storage::{EventStatus, Storage, StoredEvent},
 };
 
-use super::events::{DisplayZone, format_event_timing, parse_timezone, parse_utc};
+use super::{
+    events::{DisplayZone, format_event_timing, parse_timezone, parse_utc},
+    free::busy_intervals,
+    range::{date_range, event_filter, resolve_range},
+};
+
+/// Stand-in for titles and descriptions in redacted exports.
+const BUSY: &str = "Busy";
+/// Days covered by `freebusy` when no range is given, starting today.
+const FREEBUSY_DAYS: u64 = 30;
 
 pub fn generate_rss(storage: &Storage, cmd: RssCommand) -> Result<()> {
-    let range = if let Some(day) = cmd.day {
-        Some(super::events::day_range(&day)?)
-    } else {
-        None
-    };
     let zone = parse_timezone(cmd.tz.as_deref())?;
-    let events = storage.fetch_events(range)?;
+    let range = resolve_range(&cmd.range, &zone)?;
+    let events = storage.fetch_events(&event_filter(range, &cmd.tags))?;
 
     let mut items = Vec::new();
     for event in events {
+        if event.private && matches!(cmd.private_events, PrivateEvents::Skip) {
+            continue;
+        }
         let summary = format_event_timing(&event, &zone)?;
         let mut description_parts = vec![summary];
-        if !event.note.is_empty() {
-            description_parts.push(event.note.clone());
-        }
-        if !event.tags.is_empty() {
-            description_parts.push(format!("tags: {}", event.tags.join(", ")));
+        if !event.private {
+            if let Some(location) = &event.location {
+                description_parts.push(format!("location: {location}"));
+            }
+            if !event.note.is_empty() {
+                description_parts.push(event.note.clone());
+            }
+            if !event.tags.is_empty() {
+                description_parts.push(format!("tags: {}", event.tags.join(", ")));
+            }
         }
         let description = description_parts.join("\n");
-        let link = format!("toki-note://event/{}", event.id);
+        let title = if event.private {
+            BUSY.to_string()
+        } else {
+            event.title.clone()
+        };
+        let id_link = format!("toki-note://event/{}", event.id);
+        let link = match &event.url {
+            Some(url) if !event.private => url.clone(),
+            _ => id_link.clone(),
+        };
         let guid = GuidBuilder::default()
-            .value(link.clone())
+            .value(id_link)
             .permalink(false)
             .build();
         let pub_date = parse_utc(&event.starts_at)?.to_rfc2822();
         let item = ItemBuilder::default()
-            .title(Some(event.title.clone()))
+            .title(Some(title))
             .description(Some(description))
-            .link(Some(link.clone()))
+            .link(Some(link))
             .pub_date(Some(pub_date))
             .guid(Some(guid))
             .build();
@@ -69,19 +95,26 @@ pub fn generate_rss(storage: &Storage, cmd: RssCommand) -> Result<()> {
 }
 
 pub fn generate_ical(storage: &Storage, cmd: IcalCommand) -> Result<()> {
-    let range = if let Some(day) = cmd.day {
-        Some(super::events::day_range(&day)?)
-    } else {
-        None
-    };
     let zone = parse_timezone(cmd.tz.as_deref())?;
-    let events = storage.fetch_events(range)?;
+    let range = resolve_range(&cmd.range, &zone)?;
+    // Series are exported once with their RRULE rather than per occurrence.
+    let events = storage.fetch_event_series(&event_filter(range, &cmd.tags))?;
 
     let mut calendar = ICalendar::new("2.0", "toki-note");
     let mut emitted = false;
     for event in events {
+        if event.private && matches!(cmd.private_events, PrivateEvents::Skip) {
+            continue;
+        }
         emitted = true;
-        let uid = format!("{}@toki-note", event.id);
+        // Overrides share the UID of their series and are told apart by RECURRENCE-ID.
+        let uid = match event.parent_id {
+            Some(parent_id) => storage
+                .fetch_event_by_id(parent_id)?
+                .map(|parent| export_uid(&parent))
+                .unwrap_or_else(|| format!("{parent_id}@toki-note")),
+            None => export_uid(&event),
+        };
         let dtstamp = parse_utc(&event.starts_at)?
             .format("%Y%m%dT%H%M%SZ")
             .to_string();
@@ -97,8 +130,17 @@ pub fn generate_ical(storage: &Storage, cmd: IcalCommand) -> Result<()> {
             vevent.push(start_prop);
             vevent.push(end_prop);
         } else {
-            let (start_value, start_tz) = format_datetime_for_ics(&event.starts_at, &zone)?;
-            let (end_value, end_tz) = format_datetime_for_ics(&event.ends_at, &zone)?;
+            // A series must be anchored in the zone it repeats in, or BYDAY
+            // can land on the wrong weekday once converted.
+            let series_zone = event
+                .recurrence
+                .as_ref()
+                .and(event.tzid.as_deref())
+                .map(|name| parse_timezone(Some(name)))
+                .transpose()?;
+            let zone = series_zone.as_ref().unwrap_or(&zone);
+            let (start_value, start_tz) = format_datetime_for_ics(&event.starts_at, zone)?;
+            let (end_value, end_tz) = format_datetime_for_ics(&event.ends_at, zone)?;
             let mut start_prop = DtStart::new(start_value);
             if let Some(tz_name) = start_tz {
                 start_prop.append(parameters!("TZID" => tz_name));
@@ -111,15 +153,63 @@ pub fn generate_ical(storage: &Storage, cmd: IcalCommand) -> Result<()> {
             vevent.push(end_prop);
         }
 
-        vevent.push(Summary::new(event.title.clone()));
-        let mut description_parts = vec![format_event_timing(&event, &zone)?];
-        if !event.note.is_empty() {
-            description_parts.push(event.note.clone());
+        if let Some(rule) = &event.recurrence {
+            vevent.push(RRule::new(rule.to_string()));
+            for exdate in &event.exdates {
+                let mut prop = ExDate::new(format_reference_for_ics(exdate, event.all_day)?);
+                if event.all_day {
+                    prop.append(parameters!("VALUE" => "DATE"));
+                }
+                vevent.push(prop);
+            }
         }
-        if !event.tags.is_empty() {
-            description_parts.push(format!("tags: {}", event.tags.join(", ")));
+        if let Some(recurrence_id) = &event.recurrence_id {
+            let mut prop =
+                RecurrenceID::new(format_reference_for_ics(recurrence_id, event.all_day)?);
+            if event.all_day {
+                prop.append(parameters!("VALUE" => "DATE"));
+            }
+            vevent.push(prop);
+        }
+
+        if event.private {
+            vevent.push(Class::private());
+        }
+        if let Some(status) = event.status {
+            vevent.push(match status {
+                EventStatus::Tentative => Status::tentative(),
+                EventStatus::Confirmed => Status::confirmed(),
+                EventStatus::Cancelled => Status::cancelled(),
+            });
+        }
+        let redacted = cmd.busy_only || event.private;
+        for minutes in &event.alarms {
+            let text = if redacted { BUSY } else { event.title.as_str() };
+            vevent.add_alarm(Alarm::display(
+                Trigger::new(format_trigger(*minutes)),
+                IcsDescription::new(text.to_string()),
+            ));
+        }
+        if redacted {
+            vevent.push(Summary::new(BUSY));
+            vevent.push(IcsDescription::new(BUSY));
+        } else {
+            if let Some(location) = &event.location {
+                vevent.push(Location::new(location.clone()));
+            }
+            if let Some(url) = &event.url {
+                vevent.push(URL::new(url.clone()));
+            }
+            vevent.push(Summary::new(event.title.clone()));
+            let mut description_parts = vec![format_event_timing(&event, &zone)?];
+            if !event.note.is_empty() {
+                description_parts.push(event.note.clone());
+            }
+            if !event.tags.is_empty() {
+                description_parts.push(format!("tags: {}", event.tags.join(", ")));
+            }
+            vevent.push(IcsDescription::new(description_parts.join("\n")));
         }
-        vevent.push(IcsDescription::new(description_parts.join("\n")));
 
         calendar.add_event(vevent);
     }
@@ -131,6 +221,46 @@ pub fn generate_ical(storage: &Storage, cmd: IcalCommand) -> Result<()> {
     write_output(calendar.to_string(), cmd.output)
 }
 
+pub fn generate_freebusy(storage: &Storage, cmd: FreebusyCommand) -> Result<()> {
+    let zone = parse_timezone(cmd.tz.as_deref())?;
+    let range = match resolve_range(&cmd.range, &zone)? {
+        Some(range) => range,
+        None => {
+            let today = zone.date_of(Utc::now());
+            date_range(today, today + Days::new(FREEBUSY_DAYS), &zone)?
+        }
+    };
+    let (starts, ends) = (parse_utc(&range.starts_at)?, parse_utc(&range.ends_at)?);
+    let events = storage.fetch_events(&event_filter(Some(range), &cmd.tags))?;
+    let busy = busy_intervals(&events, Duration::zero(), cmd.all_day_busy, &zone)?;
+
+    let uid = format!(
+        "freebusy-{}-{}@toki-note",
+        format_utc_for_ics(starts),
+        format_utc_for_ics(ends)
+    );
+    let mut freebusy = FreeBusy::new(uid, format_utc_for_ics(Utc::now()));
+    freebusy.push(DtStart::new(format_utc_for_ics(starts)));
+    freebusy.push(DtEnd::new(format_utc_for_ics(ends)));
+    for (busy_start, busy_end) in busy {
+        let (busy_start, busy_end) = (busy_start.max(starts), busy_end.min(ends));
+        if busy_start >= busy_end {
+            continue;
+        }
+        let mut period = FreeBusyTime::new(format!(
+            "{}/{}",
+            format_utc_for_ics(busy_start),
+            format_utc_for_ics(busy_end)
+        ));
+        period.append(parameters!("FBTYPE" => "BUSY"));
+        freebusy.push(period);
+    }
+
+    let mut calendar = ICalendar::new("2.0", "toki-note");
+    calendar.add_freebusy(freebusy);
+    write_output(calendar.to_string(), cmd.output)
+}
+
 fn write_output(content: String, target: Option<PathBuf>) -> Result<()> {
     if let Some(path) = target {
         if let Some(parent) = path
@@ -149,6 +279,52 @@ fn write_output(content: String, target: Option<PathBuf>) -> Result<()> {
     Ok(())
 }
 
+fn export_uid(event: &StoredEvent) -> String {
+    event
+        .uid
+        .clone()
+        .unwrap_or_else(|| format!("{}@toki-note", event.id))
+}
+
+/// A `VALARM` `TRIGGER` duration for a reminder `minutes` before the start,
+/// e.g. `-PT10M` or `-P1D`.
+fn format_trigger(minutes: i64) -> String {
+    if minutes == 0 {
+        return "PT0S".to_string();
+    }
+    let sign = if minutes > 0 { "-" } else { "" };
+    let total = minutes.unsigned_abs();
+    let (days, hours, mins) = (total / 1440, total % 1440 / 60, total % 60);
+    let mut value = format!("{sign}P");
+    if days > 0 {
+        value.push_str(&format!("{days}D"));
+    }
+    if hours > 0 || mins > 0 {
+        value.push('T');
+        if hours > 0 {
+            value.push_str(&format!("{hours}H"));
+        }
+        if mins > 0 {
+            value.push_str(&format!("{mins}M"));
+        }
+    }
+    value
+}
+
+fn format_utc_for_ics(value: DateTime<Utc>) -> String {
+    value.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+/// EXDATE/RECURRENCE-ID value naming an occurrence by its original start.
+fn format_reference_for_ics(value: &str, all_day: bool) -> Result<String> {
+    let utc = parse_utc(value)?;
+    Ok(if all_day {
+        utc.format("%Y%m%d").to_string()
+    } else {
+        utc.format("%Y%m%dT%H%M%SZ").to_string()
+    })
+}
+
 fn format_datetime_for_ics(value: &str, zone: &DisplayZone) -> Result<(String, Option<String>)> {
     let utc = parse_utc(value)?;
     match zone {
@@ -0,0 +1,351 @@
+use anyhow::{Context, Result, anyhow};
+use chrono::{DateTime, Datelike, Duration, NaiveTime, Utc, Weekday};
+use humantime::{format_duration, parse_duration};
+use serde::Serialize;
+
+use crate::{
+    cli::{FreeCommand, RangeArgs, ReportFormat},
+    storage::{Storage, StoredEvent},
+};
+
+use super::{
+    calendar::event_days,
+    events::{DisplayZone, parse_timezone, parse_utc},
+    range::{event_filter, resolve_range},
+};
+
+const DEFAULT_HOURS: &str = "09:00-18:00";
+const DEFAULT_MIN_LENGTH: &str = "30m";
+/// Window searched when no range is given, starting now.
+const DEFAULT_UPCOMING: &str = "7d";
+/// Longest range `free` will walk day by day.
+const MAX_DAYS: usize = 366;
+
+type Interval = (DateTime<Utc>, DateTime<Utc>);
+
+pub fn find_free_slots(storage: &Storage, cmd: FreeCommand) -> Result<()> {
+    let tz = parse_timezone(cmd.tz.as_deref())?;
+    let hours = parse_hours(cmd.hours.as_deref().unwrap_or(DEFAULT_HOURS))?;
+    let days = parse_days(&cmd.days)?;
+    let min_length = parse_span(cmd.min.as_deref().unwrap_or(DEFAULT_MIN_LENGTH))?;
+    let buffer = match cmd.buffer.as_deref() {
+        Some(value) => parse_span(value)?,
+        None => Duration::zero(),
+    };
+
+    let range = match resolve_range(&cmd.range, &tz)? {
+        Some(range) => range,
+        None => {
+            let upcoming = RangeArgs {
+                upcoming: Some(DEFAULT_UPCOMING.to_string()),
+                ..RangeArgs::default()
+            };
+            resolve_range(&upcoming, &tz)?.ok_or_else(|| anyhow!("empty default range"))?
+        }
+    };
+    let window = (parse_utc(&range.starts_at)?, parse_utc(&range.ends_at)?);
+    let events = storage.fetch_events(&event_filter(Some(range), &cmd.tags))?;
+
+    let busy = busy_intervals(&events, buffer, cmd.all_day_busy, &tz)?;
+    let working = working_windows(window, hours, &days, &tz)?;
+    let slots = free_slots(&working, &busy, min_length);
+
+    match cmd.format {
+        ReportFormat::Json => {
+            let records: Vec<SlotRecord> = slots
+                .iter()
+                .map(|(start, end)| SlotRecord {
+                    start: start.to_rfc3339(),
+                    end: end.to_rfc3339(),
+                    start_local: tz.rfc3339(*start),
+                    end_local: tz.rfc3339(*end),
+                    minutes: (*end - *start).num_minutes(),
+                })
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&records)?);
+        }
+        ReportFormat::Text if slots.is_empty() => {
+            println!("No free slots of at least {}", describe_span(min_length));
+        }
+        ReportFormat::Text => {
+            for (start, end) in &slots {
+                let end_spec = if tz.date_of(*start) == tz.date_of(*end) {
+                    "%H:%M"
+                } else {
+                    "%a %Y-%m-%d %H:%M"
+                };
+                println!(
+                    "{}-{}  ({})",
+                    tz.format(*start, "%a %Y-%m-%d %H:%M"),
+                    tz.format(*end, end_spec),
+                    describe_span(*end - *start)
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct SlotRecord {
+    start: String,
+    end: String,
+    start_local: String,
+    end_local: String,
+    minutes: i64,
+}
+
+fn parse_hours(value: &str) -> Result<(NaiveTime, NaiveTime)> {
+    let error = || anyhow!("expected working hours as HH:MM-HH:MM, got '{value}'");
+    let (start, end) = value.split_once('-').ok_or_else(error)?;
+    let start = NaiveTime::parse_from_str(start.trim(), "%H:%M").map_err(|_| error())?;
+    let end = NaiveTime::parse_from_str(end.trim(), "%H:%M").map_err(|_| error())?;
+    if start == end {
+        return Err(error());
+    }
+    Ok((start, end))
+}
+
+fn parse_days(values: &[String]) -> Result<Vec<Weekday>> {
+    if values.is_empty() {
+        return Ok(vec![
+            Weekday::Mon,
+            Weekday::Tue,
+            Weekday::Wed,
+            Weekday::Thu,
+            Weekday::Fri,
+        ]);
+    }
+    values
+        .iter()
+        .map(|value| {
+            value
+                .trim()
+                .parse()
+                .map_err(|_| anyhow!("unknown working day '{value}'; expected e.g. mon or friday"))
+        })
+        .collect()
+}
+
+fn parse_span(value: &str) -> Result<Duration> {
+    let span =
+        parse_duration(value).with_context(|| format!("failed to parse duration '{value}'"))?;
+    Duration::from_std(span).map_err(|_| anyhow!("duration '{value}' is too large"))
+}
+
+fn describe_span(span: Duration) -> String {
+    format_duration(span.to_std().unwrap_or_default()).to_string()
+}
+
+/// Working hours on each working day inside `window`. Hours that end
+/// before they start run past midnight.
+fn working_windows(
+    window: Interval,
+    (open, close): (NaiveTime, NaiveTime),
+    days: &[Weekday],
+    zone: &DisplayZone,
+) -> Result<Vec<Interval>> {
+    let first = zone.date_of(window.0);
+    let last = zone.date_of(window.1);
+    let dates: Vec<_> = first
+        .iter_days()
+        .take_while(|date| *date <= last)
+        .take(MAX_DAYS + 1)
+        .collect();
+    if dates.len() > MAX_DAYS {
+        return Err(anyhow!(
+            "free looks at most {MAX_DAYS} days at a time; narrow the range"
+        ));
+    }
+
+    let mut windows = Vec::new();
+    for date in dates {
+        if !days.contains(&date.weekday()) {
+            continue;
+        }
+        let close_date = if close <= open {
+            date.succ_opt()
+        } else {
+            Some(date)
+        };
+        let (Some(start), Some(end)) = (
+            zone.instant(date.and_time(open)),
+            close_date.and_then(|day| zone.instant(day.and_time(close))),
+        ) else {
+            // Working hours begin or end inside a DST gap that day.
+            continue;
+        };
+        let (start, end) = (start.max(window.0), end.min(window.1));
+        if start < end {
+            windows.push((start, end));
+        }
+    }
+    Ok(windows)
+}
+
+/// Times taken by `events`, widened by `buffer` on both sides, sorted and
+/// merged. All-day events only count when `all_day_busy` is set.
+pub(super) fn busy_intervals(
+    events: &[StoredEvent],
+    buffer: Duration,
+    all_day_busy: bool,
+    zone: &DisplayZone,
+) -> Result<Vec<Interval>> {
+    let mut busy = Vec::new();
+    for event in events.iter().filter(|event| !event.is_cancelled()) {
+        if event.all_day {
+            if all_day_busy {
+                let (first, last) = event_days(event, zone)?;
+                let after = last.succ_opt().ok_or_else(|| anyhow!("date overflow"))?;
+                busy.push((zone.midnight(first)?, zone.midnight(after)?));
+            }
+        } else {
+            busy.push((
+                parse_utc(&event.starts_at)? - buffer,
+                parse_utc(&event.ends_at)? + buffer,
+            ));
+        }
+    }
+    busy.sort();
+
+    let mut merged: Vec<Interval> = Vec::new();
+    for (start, end) in busy {
+        match merged.last_mut() {
+            Some(last) if start <= last.1 => last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+    Ok(merged)
+}
+
+/// The parts of `working` not covered by `busy` that last at least
+/// `min_length`.
+fn free_slots(working: &[Interval], busy: &[Interval], min_length: Duration) -> Vec<Interval> {
+    let mut slots = Vec::new();
+    for &(start, end) in working {
+        let mut cursor = start;
+        for &(busy_start, busy_end) in busy {
+            if busy_end <= cursor {
+                continue;
+            }
+            if busy_start >= end {
+                break;
+            }
+            if busy_start > cursor {
+                slots.push((cursor, busy_start));
+            }
+            cursor = cursor.max(busy_end);
+        }
+        if cursor < end {
+            slots.push((cursor, end));
+        }
+    }
+    slots.retain(|(start, end)| *end - *start >= min_length);
+    slots
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::EventStatus;
+
+    fn event(start: &str, end: &str, all_day: bool) -> StoredEvent {
+        StoredEvent {
+            id: 1,
+            title: "Busy".into(),
+            starts_at: start.into(),
+            ends_at: end.into(),
+            all_day,
+            ..Default::default()
+        }
+    }
+
+    fn at(value: &str) -> DateTime<Utc> {
+        parse_utc(value).unwrap()
+    }
+
+    fn local(zone: &DisplayZone, slots: &[Interval]) -> Vec<String> {
+        slots
+            .iter()
+            .map(|(start, end)| {
+                format!(
+                    "{} {}",
+                    zone.format(*start, "%a %H:%M"),
+                    zone.format(*end, "%H:%M")
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn finds_gaps_within_working_hours() {
+        let tokyo = DisplayZone::Named(chrono_tz::Asia::Tokyo);
+        // Thursday 2025-08-14 and Friday 2025-08-15 afternoons in Tokyo.
+        let window = (
+            at("2025-08-14T00:00:00+00:00"),
+            at("2025-08-16T00:00:00+00:00"),
+        );
+        let hours = parse_hours("13:00-18:00").unwrap();
+        let days = parse_days(&["thu".into(), "Friday".into()]).unwrap();
+        let working = working_windows(window, hours, &days, &tokyo).unwrap();
+        assert_eq!(
+            local(&tokyo, &working),
+            vec!["Thu 13:00 18:00", "Fri 13:00 18:00"]
+        );
+
+        let mut cancelled = event(
+            "2025-08-14T07:00:00+00:00",
+            "2025-08-14T08:00:00+00:00",
+            false,
+        );
+        cancelled.status = Some(EventStatus::Cancelled);
+        let events = vec![
+            // A cancelled meeting Thu 16:00-17:00 in Tokyo leaves the time free.
+            cancelled,
+            // Thu 14:00-15:00 and 15:20-16:00 in Tokyo.
+            event(
+                "2025-08-14T05:00:00+00:00",
+                "2025-08-14T06:00:00+00:00",
+                false,
+            ),
+            event(
+                "2025-08-14T06:20:00+00:00",
+                "2025-08-14T07:00:00+00:00",
+                false,
+            ),
+            // A holiday on Friday.
+            event(
+                "2025-08-15T00:00:00+00:00",
+                "2025-08-16T00:00:00+00:00",
+                true,
+            ),
+        ];
+        let busy = busy_intervals(&events, Duration::minutes(10), false, &tokyo).unwrap();
+        let slots = free_slots(&working, &busy, Duration::minutes(45));
+        assert_eq!(
+            local(&tokyo, &slots),
+            vec!["Thu 13:00 13:50", "Thu 16:10 18:00", "Fri 13:00 18:00"]
+        );
+
+        let busy = busy_intervals(&events, Duration::zero(), true, &tokyo).unwrap();
+        let slots = free_slots(&working, &busy, Duration::minutes(20));
+        assert_eq!(
+            local(&tokyo, &slots),
+            vec!["Thu 13:00 14:00", "Thu 15:00 15:20", "Thu 16:00 18:00"]
+        );
+    }
+
+    #[test]
+    fn rejects_bad_settings() {
+        assert!(parse_hours("9-17").is_err());
+        assert!(parse_hours("09:00-09:00").is_err());
+        assert!(parse_days(&["someday".into()]).is_err());
+        let forever = (
+            at("2025-01-01T00:00:00+00:00"),
+            at("2027-01-01T00:00:00+00:00"),
+        );
+        let hours = parse_hours(DEFAULT_HOURS).unwrap();
+        let utc = DisplayZone::Named(chrono_tz::UTC);
+        assert!(working_windows(forever, hours, &[Weekday::Mon], &utc).is_err());
+    }
+}
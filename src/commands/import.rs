@@ -1,66 +1,596 @@
-use std::{fs, io::BufReader};
+use std::{
+    collections::HashMap,
+    fmt, fs,
+    io::{self, BufRead, BufReader},
+    path::{Path, PathBuf},
+};
 
 use anyhow::{Context, Result, anyhow};
 use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, TimeZone, Utc};
-use chrono_tz::Tz;
 use ical::property::Property as ParsedProperty;
 use ical::{IcalParser, parser::ical::component::IcalEvent as ParsedIcalEvent};
+use serde::Serialize;
 
 use crate::{
-    cli::ImportCommand,
-    storage::{NewEvent, Storage},
+    cli::{ImportCommand, ReportFormat},
+    recurrence::Recurrence,
+    storage::{EventStatus, ImportBatch, NewEvent, Storage, StoredRevision},
+};
+
+use super::{
+    output::report,
+    tzid::{TimeZones, iana_zone},
 };
 
+/// Events written per transaction unless `--atomic` asks for just one.
+const DEFAULT_BATCH_SIZE: usize = 1000;
+
 pub fn import_ics(storage: &mut Storage, cmd: ImportCommand) -> Result<()> {
     let path = cmd
         .path
         .as_ref()
         .ok_or_else(|| anyhow!("Provide --path or set import_source in config"))?;
-    let file =
-        fs::File::open(path).with_context(|| format!("failed to open {}", path.display()))?;
-    let reader = BufReader::new(file);
-    let parser = IcalParser::new(reader);
-
-    let mut imported = 0usize;
-    let mut skipped = 0usize;
-
-    for calendar in parser {
-        let calendar = calendar?;
-        for event in calendar.events {
-            match convert_ical_event(&event) {
-                Ok(Some(new_event)) => {
-                    let duplicate = new_event
-                        .uid
-                        .as_deref()
-                        .map(|uid| storage.has_event_with_uid(uid))
-                        .transpose()?
-                        .unwrap_or(false);
-                    if duplicate {
-                        skipped += 1;
+    let (sources, listing) = resolve_sources(path)?;
+    if sources.is_empty() {
+        eprintln!("No .ics files found in {}", path.display());
+    }
+
+    let mut run = ImportRun {
+        cmd: &cmd,
+        batch_size: cmd.batch_size.unwrap_or(DEFAULT_BATCH_SIZE).max(1),
+        pending: 0,
+        per_file: listing,
+        planned: HashMap::new(),
+    };
+    let mut batch = storage.import_batch()?;
+    let mut files = Vec::new();
+    for source in &sources {
+        if run.per_file {
+            batch.begin_file()?;
+        }
+        let result = source
+            .open()
+            .and_then(|reader| run.import_calendar(&mut batch, reader));
+        match result {
+            Ok(mut file) => {
+                file.file = listing.then(|| source.to_string());
+                files.push(file);
+                if run.per_file {
+                    batch.keep_file()?;
+                }
+            }
+            Err(err) if cmd.atomic && !cmd.dry_run => {
+                return Err(err.context(format!(
+                    "import rolled back at {source}; nothing was written"
+                )));
+            }
+            // One unreadable file should not hold up the rest of a directory,
+            // and none of it is kept, even events read before the error.
+            Err(err) if listing => {
+                batch.discard_file()?;
+                eprintln!("Skipping {source}: {err:#}");
+            }
+            Err(err) => return Err(err),
+        }
+        if run.per_file {
+            run.checkpoint_if_due(&mut batch)?;
+        }
+    }
+    if !cmd.dry_run {
+        batch.commit()?;
+    }
+
+    let mut total = ImportSummary::default();
+    for file in &files {
+        total.add(&file.summary);
+    }
+    if cmd.dry_run {
+        print_plan(&files, &total, listing, cmd.format)
+    } else if listing {
+        let mut lines: Vec<String> = files
+            .iter()
+            .map(|file| {
+                format!(
+                    "{}: {}",
+                    file.file.as_deref().unwrap_or_default(),
+                    file.summary
+                )
+            })
+            .collect();
+        lines.push(format!("Imported {total}"));
+        let json = ImportReport {
+            files: &files,
+            total: &total,
+        };
+        report(cmd.format, lines.join("\n"), json)
+    } else {
+        report(cmd.format, format!("Imported {total}"), &total)
+    }
+}
+
+/// Somewhere to read a calendar from.
+enum Source {
+    Stdin,
+    File(PathBuf),
+}
+
+impl Source {
+    fn open(&self) -> Result<Box<dyn BufRead>> {
+        Ok(match self {
+            Source::Stdin => Box::new(io::stdin().lock()),
+            Source::File(path) => Box::new(BufReader::new(
+                fs::File::open(path)
+                    .with_context(|| format!("failed to open {}", path.display()))?,
+            )),
+        })
+    }
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Source::Stdin => f.write_str("stdin"),
+            Source::File(path) => write!(f, "{}", path.display()),
+        }
+    }
+}
+
+/// The calendars `path` names: `-` for stdin, a file, every `.ics` file in
+/// a directory (such as a vdir), or the files matching a glob. The flag
+/// tells whether `path` listed files rather than naming one.
+fn resolve_sources(path: &Path) -> Result<(Vec<Source>, bool)> {
+    if path == Path::new("-") {
+        return Ok((vec![Source::Stdin], false));
+    }
+    let mut files = if path.is_dir() {
+        let mut files = Vec::new();
+        for entry in
+            fs::read_dir(path).with_context(|| format!("failed to read {}", path.display()))?
+        {
+            let file = entry?.path();
+            let is_ics = file
+                .extension()
+                .is_some_and(|extension| extension.eq_ignore_ascii_case("ics"));
+            if is_ics && file.is_file() {
+                files.push(file);
+            }
+        }
+        files
+    } else {
+        let pattern = path.to_string_lossy();
+        if path.exists() || !pattern.contains(['*', '?', '[']) {
+            return Ok((vec![Source::File(path.to_path_buf())], false));
+        }
+        let matches =
+            glob::glob(&pattern).with_context(|| format!("invalid glob pattern '{pattern}'"))?;
+        let mut files = Vec::new();
+        for file in matches {
+            let file = file?;
+            if file.is_file() {
+                files.push(file);
+            }
+        }
+        files
+    };
+    files.sort();
+    Ok((files.into_iter().map(Source::File).collect(), true))
+}
+
+/// Settings and progress shared by every calendar in one import.
+struct ImportRun<'a> {
+    cmd: &'a ImportCommand,
+    batch_size: usize,
+    /// Events handled since the batch was last checkpointed.
+    pending: usize,
+    /// Each file is kept or undone as a whole, so checkpoints wait for
+    /// the end of a file.
+    per_file: bool,
+    /// Revisions `--dry-run` would have written, by UID and RECURRENCE-ID.
+    /// Nothing it plans reaches the database, so later events in the same
+    /// run are compared against these first.
+    planned: HashMap<(String, Option<String>), StoredRevision>,
+}
+
+impl ImportRun<'_> {
+    fn import_calendar(
+        &mut self,
+        batch: &mut ImportBatch<'_>,
+        reader: impl BufRead,
+    ) -> Result<FileReport> {
+        let cmd = self.cmd;
+        let mut summary = ImportSummary::default();
+        // Only kept for --dry-run, which reports on every event.
+        let mut rows = Vec::new();
+        let mut note = |summary: &mut ImportSummary, row: PlannedRow| {
+            summary.record(row.outcome);
+            if cmd.dry_run {
+                rows.push(row);
+            }
+        };
+        // Overridden occurrences are linked to their series by UID once every
+        // series in the file has been stored.
+        let mut overrides = Vec::new();
+
+        for calendar in IcalParser::new(reader) {
+            let calendar = calendar?;
+            let zones = TimeZones::new(&calendar.timezones);
+            for event in calendar.events {
+                let converted = convert_ical_event(&event, &zones)
+                    .and_then(|new_event| new_event.ok_or_else(|| anyhow!("no DTSTART")));
+                let new_event = match converted {
+                    Ok(new_event) if new_event.recurrence_id.is_some() => {
+                        overrides.push(new_event);
+                        continue;
+                    }
+                    Ok(new_event) => new_event,
+                    Err(err) if cmd.atomic && !cmd.dry_run => return Err(err),
+                    Err(err) => {
+                        if !cmd.dry_run {
+                            eprintln!("Skipping event: {err}");
+                        }
+                        note(&mut summary, PlannedRow::invalid(&event, err));
                         continue;
                     }
-                    storage.insert_event(new_event)?;
-                    imported += 1;
+                };
+                let stored = match &new_event.uid {
+                    Some(uid) => match self.planned.get(&(uid.clone(), None)) {
+                        Some(planned) => Some(planned.clone()),
+                        None => batch.revision_by_uid(uid)?,
+                    },
+                    None => None,
+                };
+                let (outcome, reason) = match &stored {
+                    Some(stored) => compare_revisions(&new_event, stored),
+                    None if new_event.uid.is_some() => {
+                        (Outcome::Created, "UID not seen before".into())
+                    }
+                    None => (Outcome::Created, "no UID to match on".into()),
+                };
+                let row = PlannedRow::new(outcome, reason, &new_event);
+                if cmd.dry_run {
+                    self.plan(outcome, None, &new_event);
+                } else {
+                    match (outcome, stored) {
+                        (Outcome::Created, _) => {
+                            batch.insert_event(new_event)?;
+                        }
+                        (Outcome::Updated | Outcome::Cancelled, Some(stored)) => {
+                            batch.replace_event(stored.id, new_event)?;
+                        }
+                        _ => {}
+                    }
+                }
+                note(&mut summary, row);
+                self.handled(batch)?;
+            }
+        }
+
+        for mut new_event in overrides {
+            let recurrence_id = new_event
+                .recurrence_id
+                .take()
+                .expect("override should carry RECURRENCE-ID");
+            let parent_id = new_event
+                .uid
+                .as_deref()
+                .map(|uid| batch.event_id_by_uid(uid))
+                .transpose()?
+                .flatten();
+            let planned = new_event.uid.as_ref().and_then(|uid| {
+                self.planned
+                    .get(&(uid.clone(), Some(recurrence_id.clone())))
+                    .cloned()
+            });
+            let has_series = parent_id.is_some()
+                || new_event
+                    .uid
+                    .as_ref()
+                    .is_some_and(|uid| self.planned.contains_key(&(uid.clone(), None)));
+            let stored = match (planned, parent_id, new_event.uid.as_deref()) {
+                (Some(planned), _, _) => Some(planned),
+                (None, Some(parent_id), _) => batch.override_revision(parent_id, &recurrence_id)?,
+                (None, None, Some(uid)) if !has_series => {
+                    batch.detached_override_revision(uid, &recurrence_id)?
                 }
-                Ok(None) => skipped += 1,
-                Err(err) => {
-                    skipped += 1;
-                    eprintln!("Skipping event: {err}");
+                _ => None,
+            };
+            let (outcome, reason) = match (&stored, has_series) {
+                (Some(stored), _) => compare_revisions(&new_event, stored),
+                (None, true) => (Outcome::Created, "occurrence not changed before".into()),
+                (None, false) => (
+                    Outcome::Created,
+                    "changed occurrence whose series is not stored".into(),
+                ),
+            };
+            note(&mut summary, PlannedRow::new(outcome, reason, &new_event));
+            if cmd.dry_run {
+                self.plan(outcome, Some(recurrence_id), &new_event);
+            } else if outcome != Outcome::Unchanged {
+                match (parent_id, stored) {
+                    (Some(parent_id), _) => {
+                        batch.upsert_occurrence_override(parent_id, &recurrence_id, new_event)?;
+                    }
+                    // Without its series the occurrence is stored as an event
+                    // of its own, keeping UID and RECURRENCE-ID so the next
+                    // import of it finds this copy.
+                    (None, Some(stored)) => {
+                        new_event.recurrence_id = Some(recurrence_id);
+                        batch.replace_event(stored.id, new_event)?;
+                    }
+                    (None, None) => {
+                        new_event.recurrence_id = Some(recurrence_id);
+                        batch.insert_event(new_event)?;
+                    }
                 }
             }
+            self.handled(batch)?;
+        }
+
+        Ok(FileReport {
+            file: None,
+            events: cmd.dry_run.then_some(rows),
+            summary,
+        })
+    }
+
+    /// Remembers the revision `--dry-run` would have written for
+    /// `new_event`, so a later copy of it is compared with this one.
+    fn plan(&mut self, outcome: Outcome, recurrence_id: Option<String>, new_event: &NewEvent) {
+        let Some(uid) = &new_event.uid else { return };
+        if outcome == Outcome::Unchanged {
+            return;
+        }
+        self.planned.insert(
+            (uid.clone(), recurrence_id),
+            StoredRevision {
+                // Never written, so there is no row to point at.
+                id: 0,
+                sequence: new_event.sequence,
+                last_modified: new_event.last_modified.clone(),
+                status: new_event.status,
+            },
+        );
+    }
+
+    /// Counts one event, checkpointing mid-file only when files need not
+    /// be undone as a whole.
+    fn handled(&mut self, batch: &mut ImportBatch<'_>) -> Result<()> {
+        self.pending += 1;
+        if self.per_file {
+            return Ok(());
+        }
+        self.checkpoint_if_due(batch)
+    }
+
+    /// Commits once `batch_size` events are pending, unless everything is
+    /// to land in one transaction or nothing is to be written at all.
+    fn checkpoint_if_due(&mut self, batch: &mut ImportBatch<'_>) -> Result<()> {
+        if self.pending >= self.batch_size && !self.cmd.atomic && !self.cmd.dry_run {
+            batch.checkpoint()?;
+            self.pending = 0;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize)]
+struct ImportReport<'a> {
+    files: &'a [FileReport],
+    total: &'a ImportSummary,
+}
+
+/// How one calendar fared; `file` is only set when importing several.
+#[derive(Serialize)]
+struct FileReport {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    file: Option<String>,
+    /// Per-event outcomes, kept for `--dry-run`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    events: Option<Vec<PlannedRow>>,
+    summary: ImportSummary,
+}
+
+/// What importing one event does to the database.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Outcome {
+    Created,
+    Updated,
+    Unchanged,
+    Cancelled,
+    Skipped,
+}
+
+impl Outcome {
+    /// The `--dry-run` label; cancellations are updates of their own kind.
+    fn label(self) -> &'static str {
+        match self {
+            Outcome::Created => "new",
+            Outcome::Updated | Outcome::Cancelled => "would-update",
+            Outcome::Unchanged => "duplicate",
+            Outcome::Skipped => "invalid",
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+struct ImportSummary {
+    created: usize,
+    updated: usize,
+    unchanged: usize,
+    cancelled: usize,
+    skipped: usize,
+}
+
+impl ImportSummary {
+    fn add(&mut self, other: &ImportSummary) {
+        self.created += other.created;
+        self.updated += other.updated;
+        self.unchanged += other.unchanged;
+        self.cancelled += other.cancelled;
+        self.skipped += other.skipped;
+    }
+
+    fn record(&mut self, outcome: Outcome) {
+        match outcome {
+            Outcome::Created => self.created += 1,
+            Outcome::Updated => self.updated += 1,
+            Outcome::Unchanged => self.unchanged += 1,
+            Outcome::Cancelled => self.cancelled += 1,
+            Outcome::Skipped => self.skipped += 1,
+        }
+    }
+}
+
+impl fmt::Display for ImportSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} new, {} updated, {} unchanged, {} cancelled event(s), skipped {}",
+            self.created, self.updated, self.unchanged, self.cancelled, self.skipped
+        )
+    }
+}
+
+/// One line of the `--dry-run` report.
+#[derive(Serialize)]
+struct PlannedRow {
+    action: &'static str,
+    #[serde(skip)]
+    outcome: Outcome,
+    uid: Option<String>,
+    title: Option<String>,
+    start: Option<String>,
+    reason: String,
+}
+
+impl PlannedRow {
+    fn new(outcome: Outcome, reason: String, event: &NewEvent) -> Self {
+        Self {
+            action: outcome.label(),
+            outcome,
+            uid: event.uid.clone(),
+            title: Some(event.title.clone()),
+            start: Some(event.starts_at.clone()),
+            reason,
         }
     }
 
-    println!("Imported {imported} event(s), skipped {skipped}");
+    /// An event that could not be read, described by whatever it carries.
+    fn invalid(event: &ParsedIcalEvent, reason: impl fmt::Display) -> Self {
+        let text = |name| get_property(event, name).and_then(parse_text);
+        Self {
+            action: Outcome::Skipped.label(),
+            outcome: Outcome::Skipped,
+            uid: text("UID"),
+            title: text("SUMMARY"),
+            start: get_property(event, "DTSTART").and_then(|prop| prop.value.clone()),
+            reason: reason.to_string(),
+        }
+    }
+}
+
+fn print_plan(
+    files: &[FileReport],
+    total: &ImportSummary,
+    listing: bool,
+    format: ReportFormat,
+) -> Result<()> {
+    match format {
+        ReportFormat::Json if listing => {
+            let plan = ImportReport { files, total };
+            println!("{}", serde_json::to_string_pretty(&plan)?);
+        }
+        ReportFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(&files.first())?);
+        }
+        ReportFormat::Text => {
+            for file in files {
+                if let Some(name) = &file.file {
+                    println!("{name}: {}", file.summary);
+                }
+                print_table(file.events.as_deref().unwrap_or_default());
+                if listing {
+                    println!();
+                }
+            }
+            println!("Dry run, nothing written: {total}");
+        }
+    }
     Ok(())
 }
 
-fn convert_ical_event(event: &ParsedIcalEvent) -> Result<Option<NewEvent>> {
+fn print_table(rows: &[PlannedRow]) {
+    let cells: Vec<[String; 4]> = rows
+        .iter()
+        .map(|row| {
+            [
+                row.action.to_string(),
+                row.start.clone().unwrap_or_default(),
+                row.title
+                    .clone()
+                    .or_else(|| row.uid.clone())
+                    .unwrap_or_default(),
+                row.reason.clone(),
+            ]
+        })
+        .collect();
+    let header = ["ACTION", "START", "EVENT", "REASON"].map(String::from);
+    let mut widths = header.clone().map(|cell| cell.chars().count());
+    for row in &cells {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    for row in std::iter::once(&header).chain(&cells) {
+        let line = row
+            .iter()
+            .zip(widths)
+            .map(|(cell, width)| format!("{cell:<width$}"))
+            .collect::<Vec<_>>()
+            .join("  ");
+        println!("{}", line.trim_end());
+    }
+}
+
+/// Whether `incoming` replaces the copy already stored under its UID, and
+/// why. It does when it is a later revision: a higher `SEQUENCE`, or the
+/// same one with a later `LAST-MODIFIED`. A cancellation also applies when
+/// it carries the stored revision, since not every calendar bumps
+/// `SEQUENCE` for one.
+fn compare_revisions(incoming: &NewEvent, stored: &StoredRevision) -> (Outcome, String) {
+    let incoming_revision = (incoming.sequence, incoming.last_modified.as_deref());
+    let stored_revision = (stored.sequence, stored.last_modified.as_deref());
+    let cancelled = incoming.status == Some(EventStatus::Cancelled)
+        && stored.status != Some(EventStatus::Cancelled);
+    if cancelled && incoming_revision >= stored_revision {
+        (Outcome::Cancelled, "cancelled in the source".into())
+    } else if incoming.sequence > stored.sequence {
+        (
+            Outcome::Updated,
+            format!(
+                "SEQUENCE {} is newer than {}",
+                incoming.sequence, stored.sequence
+            ),
+        )
+    } else if incoming_revision > stored_revision {
+        (
+            Outcome::Updated,
+            format!(
+                "LAST-MODIFIED {} is newer than stored copy",
+                incoming.last_modified.as_deref().unwrap_or_default()
+            ),
+        )
+    } else {
+        (Outcome::Unchanged, "stored copy is as new".into())
+    }
+}
+
+fn convert_ical_event(event: &ParsedIcalEvent, zones: &TimeZones) -> Result<Option<NewEvent>> {
     let (starts_at, all_day) = match get_property(event, "DTSTART") {
-        Some(prop) => parse_ics_datetime(prop)?,
+        Some(prop) => parse_ics_datetime(prop, zones)?,
         None => return Ok(None),
     };
-    let ends_at = parse_ics_end(event, all_day, &starts_at)?;
+    let ends_at = parse_ics_end(event, all_day, &starts_at, zones)?;
     let title = get_property(event, "SUMMARY")
         .and_then(parse_text)
         .filter(|s| !s.is_empty())
@@ -78,6 +608,87 @@ fn convert_ical_event(event: &ParsedIcalEvent) -> Result<Option<NewEvent>> {
         })
         .unwrap_or_default();
     let uid = get_property(event, "UID").and_then(parse_text);
+    // CONFIDENTIAL is stricter than PRIVATE, so it stays off feeds too.
+    let private = get_property(event, "CLASS")
+        .and_then(|prop| prop.value.as_deref())
+        .is_some_and(|class| {
+            class.eq_ignore_ascii_case("PRIVATE") || class.eq_ignore_ascii_case("CONFIDENTIAL")
+        });
+    let location = get_property(event, "LOCATION")
+        .and_then(parse_text)
+        .filter(|s| !s.is_empty());
+    let url = get_property(event, "URL")
+        .and_then(|prop| prop.value.clone())
+        .filter(|s| !s.is_empty());
+    // Statuses meant for to-dos and journal entries are dropped.
+    let status = get_property(event, "STATUS")
+        .and_then(|prop| prop.value.as_deref())
+        .and_then(|value| value.parse::<EventStatus>().ok());
+    let recurrence = match get_property(event, "RRULE").and_then(|prop| prop.value.as_deref()) {
+        Some(value) => match value.parse::<Recurrence>() {
+            Ok(rule) => Some(rule),
+            Err(err) => {
+                eprintln!("Importing only the first occurrence of '{title}': {err}");
+                None
+            }
+        },
+        None => None,
+    };
+    let exdates = parse_exdates(event, zones)?;
+    let mut alarms = Vec::new();
+    for alarm in &event.alarms {
+        let Some(trigger) = alarm
+            .properties
+            .iter()
+            .find(|prop| prop.name.eq_ignore_ascii_case("TRIGGER"))
+        else {
+            continue;
+        };
+        match parse_trigger(trigger, &starts_at, &ends_at) {
+            Ok(minutes) if !alarms.contains(&minutes) => alarms.push(minutes),
+            Ok(_) => {}
+            Err(err) => eprintln!("Ignoring a reminder on '{title}': {err}"),
+        }
+    }
+    let recurrence_id = get_property(event, "RECURRENCE-ID")
+        .map(|prop| parse_ics_datetime(prop, zones).map(|(instant, _)| instant.to_rfc3339()))
+        .transpose()?;
+    let sequence = get_property(event, "SEQUENCE")
+        .and_then(|prop| prop.value.as_deref())
+        .and_then(|value| value.trim().parse().ok())
+        .unwrap_or(0);
+    let last_modified = get_property(event, "LAST-MODIFIED")
+        .and_then(|prop| prop.value.as_deref())
+        .and_then(|value| parse_datetime_value(value, None, zones).ok())
+        .map(|instant| instant.to_rfc3339());
+    // Keep the source zone so the series repeats on the right local weekday,
+    // and a start given in UTC keeps repeating in UTC rather than drifting
+    // with the system zone's DST changes. A series in a zone defined only by
+    // the calendar's VTIMEZONE blocks keeps an IANA zone with the same rules.
+    let start_prop = get_property(event, "DTSTART");
+    let source_zone = start_prop.and_then(|prop| property_param(prop, "TZID"));
+    let starts_in_utc = start_prop
+        .and_then(|prop| prop.value.as_deref())
+        .is_some_and(|value| value.ends_with('Z'));
+    let tzid = source_zone
+        .and_then(|zone| {
+            iana_zone(zone).or_else(|| {
+                recurrence
+                    .as_ref()
+                    .and_then(|_| zones.matching_iana_zone(zone, starts_at))
+            })
+        })
+        .map(|zone| zone.name().to_string())
+        .or_else(|| starts_in_utc.then(|| "UTC".to_string()));
+    if let Some(zone) = source_zone
+        && tzid.is_none()
+        && recurrence.is_some()
+    {
+        eprintln!(
+            "'{title}' repeats in '{zone}', which only this calendar defines and \
+             no known timezone matches; its later occurrences follow your system timezone"
+        );
+    }
 
     Ok(Some(NewEvent {
         title,
@@ -87,9 +698,53 @@ fn convert_ical_event(event: &ParsedIcalEvent) -> Result<Option<NewEvent>> {
         all_day,
         tags,
         uid,
+        recurrence,
+        tzid,
+        exdates,
+        recurrence_id,
+        private,
+        location,
+        url,
+        status,
+        alarms,
+        sequence,
+        last_modified,
+        ..NewEvent::default()
     }))
 }
 
+fn parse_exdates(event: &ParsedIcalEvent, zones: &TimeZones) -> Result<Vec<String>> {
+    let mut exdates = Vec::new();
+    for prop in event
+        .properties
+        .iter()
+        .filter(|prop| prop.name.eq_ignore_ascii_case("EXDATE"))
+    {
+        let tzid = property_param(prop, "TZID").map(|s| s.as_str());
+        let date_only =
+            property_param(prop, "VALUE").is_some_and(|v| v.eq_ignore_ascii_case("DATE"));
+        for value in prop
+            .value
+            .as_deref()
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+        {
+            let instant = if date_only || value.len() == 8 {
+                parse_date_value(value)?
+                    .and_hms_opt(0, 0, 0)
+                    .ok_or_else(|| anyhow!("invalid EXDATE '{value}'"))?
+                    .and_utc()
+            } else {
+                parse_datetime_value(value, tzid, zones)?
+            };
+            exdates.push(instant.to_rfc3339());
+        }
+    }
+    Ok(exdates)
+}
+
 fn get_property<'a>(event: &'a ParsedIcalEvent, name: &str) -> Option<&'a ParsedProperty> {
     event
         .properties
@@ -101,7 +756,7 @@ fn parse_text(prop: &ParsedProperty) -> Option<String> {
     prop.value.as_ref().map(|value| unescape_ics_text(value))
 }
 
-fn parse_ics_datetime(prop: &ParsedProperty) -> Result<(DateTime<Utc>, bool)> {
+fn parse_ics_datetime(prop: &ParsedProperty, zones: &TimeZones) -> Result<(DateTime<Utc>, bool)> {
     let value = prop
         .value
         .as_ref()
@@ -115,7 +770,7 @@ fn parse_ics_datetime(prop: &ParsedProperty) -> Result<(DateTime<Utc>, bool)> {
         return Ok((start, true));
     }
     let tzid = property_param(prop, "TZID").map(|s| s.as_str());
-    let dt = parse_datetime_value(value, tzid)?;
+    let dt = parse_datetime_value(value, tzid, zones)?;
     Ok((dt, false))
 }
 
@@ -123,6 +778,7 @@ fn parse_ics_end(
     event: &ParsedIcalEvent,
     all_day: bool,
     start: &DateTime<Utc>,
+    zones: &TimeZones,
 ) -> Result<DateTime<Utc>> {
     if let Some(prop) = get_property(event, "DTEND") {
         if all_day || is_all_day(prop) {
@@ -143,6 +799,7 @@ fn parse_ics_end(
                     .as_deref()
                     .ok_or_else(|| anyhow!("DTEND missing value"))?,
                 tzid,
+                zones,
             )?;
             return Ok(dt);
         }
@@ -155,21 +812,75 @@ fn parse_ics_end(
     }
 }
 
-fn parse_datetime_value(value: &str, tzid: Option<&str>) -> Result<DateTime<Utc>> {
+/// Minutes before the event start at which a `VALARM` `TRIGGER` fires.
+fn parse_trigger(prop: &ParsedProperty, start: &DateTime<Utc>, end: &DateTime<Utc>) -> Result<i64> {
+    let value = prop
+        .value
+        .as_deref()
+        .map(str::trim)
+        .ok_or_else(|| anyhow!("TRIGGER missing value"))?;
+    let absolute =
+        property_param(prop, "VALUE").is_some_and(|v| v.eq_ignore_ascii_case("DATE-TIME"));
+    let fires_at = if absolute {
+        parse_datetime_value(value, None, &TimeZones::default())?
+    } else {
+        let anchor = match property_param(prop, "RELATED") {
+            Some(related) if related.eq_ignore_ascii_case("END") => end,
+            _ => start,
+        };
+        *anchor + parse_ics_duration(value)?
+    };
+    Ok((*start - fires_at).num_minutes())
+}
+
+/// An RFC 5545 duration such as `-PT15M`, `P1DT2H` or `-P1W`.
+fn parse_ics_duration(value: &str) -> Result<Duration> {
+    let error = || anyhow!("invalid duration '{value}'");
+    let (negative, rest) = match value.as_bytes().first() {
+        Some(b'-') => (true, &value[1..]),
+        Some(b'+') => (false, &value[1..]),
+        _ => (false, value),
+    };
+    let rest = rest.strip_prefix(['P', 'p']).ok_or_else(error)?;
+    let mut total = Duration::zero();
+    let mut number = String::new();
+    let mut in_time = false;
+    for ch in rest.chars() {
+        match ch.to_ascii_uppercase() {
+            'T' if number.is_empty() => in_time = true,
+            digit if digit.is_ascii_digit() => number.push(digit),
+            unit => {
+                let amount: i64 = number.parse().map_err(|_| error())?;
+                number.clear();
+                total += match (unit, in_time) {
+                    ('W', false) => Duration::weeks(amount),
+                    ('D', false) => Duration::days(amount),
+                    ('H', true) => Duration::hours(amount),
+                    ('M', true) => Duration::minutes(amount),
+                    ('S', true) => Duration::seconds(amount),
+                    _ => return Err(error()),
+                };
+            }
+        }
+    }
+    if !number.is_empty() {
+        return Err(error());
+    }
+    Ok(if negative { -total } else { total })
+}
+
+fn parse_datetime_value(
+    value: &str,
+    tzid: Option<&str>,
+    zones: &TimeZones,
+) -> Result<DateTime<Utc>> {
     if let Some(stripped) = value.strip_suffix('Z') {
         let naive = NaiveDateTime::parse_from_str(stripped, "%Y%m%dT%H%M%S")?;
         return Ok(Utc.from_utc_datetime(&naive));
     }
     if let Some(zone) = tzid {
-        let tz: Tz = zone
-            .parse()
-            .map_err(|_| anyhow!("unknown timezone '{zone}'"))?;
         let naive = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S")?;
-        let localized = tz
-            .from_local_datetime(&naive)
-            .single()
-            .ok_or_else(|| anyhow!("ambiguous local time for {value} in {zone}"))?;
-        return Ok(localized.with_timezone(&Utc));
+        return zones.instant(zone, &naive);
     }
     let naive = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S")?;
     Ok(Utc.from_utc_datetime(&naive))
@@ -1,7 +1,25 @@
+mod calendar;
+mod conflicts;
+mod edit;
 mod events;
 mod feeds;
+mod free;
 mod import;
+mod output;
+mod range;
+mod remind;
+mod search;
+mod template;
+mod tui;
+mod tzid;
 
+pub use calendar::{show_month, show_week};
+pub use conflicts::list_conflicts;
+pub use edit::edit_event;
 pub use events::{add_event, delete_event, list_events, move_event};
-pub use feeds::{generate_ical, generate_rss};
+pub use feeds::{generate_freebusy, generate_ical, generate_rss};
+pub use free::find_free_slots;
 pub use import::import_ics;
+pub use remind::run_reminders;
+pub use search::search_events;
+pub use tui::run_tui;
@@ -0,0 +1,155 @@
+use std::{fmt::Display, io::Write};
+
+use anyhow::Result;
+use serde::Serialize;
+
+use crate::{
+    cli::{ListFormat, ReportFormat},
+    storage::StoredEvent,
+};
+
+use super::events::{DisplayZone, parse_utc};
+
+/// The machine-readable view of an event. Field names are part of the
+/// `--format` contract; add new ones rather than renaming.
+#[derive(Serialize)]
+pub(super) struct EventRecord {
+    id: i64,
+    uid: Option<String>,
+    title: String,
+    start: String,
+    end: String,
+    start_local: String,
+    end_local: String,
+    all_day: bool,
+    tags: Vec<String>,
+    note: String,
+    private: bool,
+    location: Option<String>,
+    url: Option<String>,
+    status: Option<String>,
+    /// Minutes before the start.
+    reminders: Vec<i64>,
+}
+
+const CSV_HEADER: [&str; 15] = [
+    "id",
+    "uid",
+    "title",
+    "start",
+    "end",
+    "start_local",
+    "end_local",
+    "all_day",
+    "tags",
+    "note",
+    "private",
+    "location",
+    "url",
+    "status",
+    "reminders",
+];
+
+impl EventRecord {
+    pub(super) fn new(event: &StoredEvent, zone: &DisplayZone) -> Result<Self> {
+        let start = parse_utc(&event.starts_at)?;
+        let end = parse_utc(&event.ends_at)?;
+        Ok(Self {
+            id: event.id,
+            uid: event.uid.clone(),
+            title: event.title.clone(),
+            start: start.to_rfc3339(),
+            end: end.to_rfc3339(),
+            start_local: zone.rfc3339(start),
+            end_local: zone.rfc3339(end),
+            all_day: event.all_day,
+            tags: event.tags.clone(),
+            note: event.note.clone(),
+            private: event.private,
+            location: event.location.clone(),
+            url: event.url.clone(),
+            status: event.status.map(|status| status.to_string()),
+            reminders: event.alarms.clone(),
+        })
+    }
+
+    fn csv_fields(&self) -> [String; 15] {
+        [
+            self.id.to_string(),
+            self.uid.clone().unwrap_or_default(),
+            self.title.clone(),
+            self.start.clone(),
+            self.end.clone(),
+            self.start_local.clone(),
+            self.end_local.clone(),
+            self.all_day.to_string(),
+            self.tags.join(";"),
+            self.note.clone(),
+            self.private.to_string(),
+            self.location.clone().unwrap_or_default(),
+            self.url.clone().unwrap_or_default(),
+            self.status.clone().unwrap_or_default(),
+            self.reminders
+                .iter()
+                .map(|minutes| minutes.to_string())
+                .collect::<Vec<_>>()
+                .join(";"),
+        ]
+    }
+}
+
+/// Writes `events` to stdout in one of the machine-readable list formats.
+pub(super) fn write_events(
+    events: &[StoredEvent],
+    format: ListFormat,
+    zone: &DisplayZone,
+) -> Result<()> {
+    let records = events
+        .iter()
+        .map(|event| EventRecord::new(event, zone))
+        .collect::<Result<Vec<_>>>()?;
+    let mut out = std::io::stdout().lock();
+    match format {
+        ListFormat::Text => unreachable!("text output is rendered by list_events"),
+        ListFormat::Json => {
+            serde_json::to_writer_pretty(&mut out, &records)?;
+            writeln!(out)?;
+        }
+        ListFormat::Ndjson => {
+            for record in &records {
+                serde_json::to_writer(&mut out, record)?;
+                writeln!(out)?;
+            }
+        }
+        ListFormat::Csv | ListFormat::Tsv => {
+            let delimiter = if matches!(format, ListFormat::Tsv) {
+                b'\t'
+            } else {
+                b','
+            };
+            let mut writer = csv::WriterBuilder::new()
+                .delimiter(delimiter)
+                .from_writer(out);
+            writer.write_record(CSV_HEADER)?;
+            for record in &records {
+                writer.write_record(record.csv_fields())?;
+            }
+            writer.flush()?;
+        }
+    }
+    Ok(())
+}
+
+/// Prints the outcome of a command that changes events: `message` for
+/// people, `json` for `--format json`.
+pub(super) fn report(
+    format: ReportFormat,
+    message: impl Display,
+    json: impl Serialize,
+) -> Result<()> {
+    match format {
+        ReportFormat::Text => println!("{message}"),
+        ReportFormat::Json => println!("{}", serde_json::to_string(&json)?),
+    }
+    Ok(())
+}
@@ -0,0 +1,327 @@
+use anyhow::{Context, Result, anyhow};
+use chrono::{DateTime, Datelike, Days, Months, NaiveDate, Utc};
+use humantime::parse_duration;
+
+use crate::{
+    cli::{RangeArgs, TagArgs},
+    storage::{EventFilter, EventRange},
+};
+
+use super::events::{DisplayZone, parse_date, utc_midnight};
+
+/// Bounds used for open-ended `--from`/`--to` windows. Stored timestamps are
+/// RFC3339 strings compared lexically, so these must keep the same shape.
+const EARLIEST: &str = "0001-01-01T00:00:00+00:00";
+const LATEST: &str = "9999-12-31T23:59:59+00:00";
+
+/// One end of a window: the instant used for timed events and the date used
+/// for all-day events (inclusive at the start, exclusive at the end).
+struct Bound {
+    instant: DateTime<Utc>,
+    date: NaiveDate,
+}
+
+impl Bound {
+    fn at_date(date: NaiveDate, zone: &DisplayZone) -> Result<Self> {
+        Ok(Self {
+            instant: zone.midnight(date)?,
+            date,
+        })
+    }
+
+    fn starting_at(instant: DateTime<Utc>, zone: &DisplayZone) -> Self {
+        Self {
+            instant,
+            date: zone.date_of(instant),
+        }
+    }
+
+    /// An end instant partway through a day still covers that day's all-day
+    /// events.
+    fn ending_at(instant: DateTime<Utc>, zone: &DisplayZone) -> Result<Self> {
+        let date = zone.date_of(instant);
+        let date = if instant > zone.midnight(date)? {
+            next_day(date)?
+        } else {
+            date
+        };
+        Ok(Self { instant, date })
+    }
+
+    fn timestamps(&self) -> Result<(String, String)> {
+        Ok((
+            self.instant.to_rfc3339(),
+            utc_midnight(self.date)?.to_rfc3339(),
+        ))
+    }
+}
+
+/// Turns the range flags into the window understood by
+/// `Storage::fetch_events`, or `None` when no filter was requested. Day
+/// boundaries are midnights in `zone`, or in UTC with `--utc-day`.
+pub(super) fn resolve_range(args: &RangeArgs, zone: &DisplayZone) -> Result<Option<EventRange>> {
+    let utc = DisplayZone::Named(chrono_tz::UTC);
+    let zone = if args.utc_day { &utc } else { zone };
+
+    let (start, end) = if let Some(day) = args.day.as_deref() {
+        let date = parse_date(day)?;
+        (
+            Some(Bound::at_date(date, zone)?),
+            Some(Bound::at_date(next_day(date)?, zone)?),
+        )
+    } else if let Some(week) = args.week.as_deref() {
+        let date = parse_date(week)?;
+        let monday = date
+            .checked_sub_days(Days::new(date.weekday().num_days_from_monday() as u64))
+            .ok_or_else(|| anyhow!("date overflow"))?;
+        let next_monday = monday
+            .checked_add_days(Days::new(7))
+            .ok_or_else(|| anyhow!("date overflow"))?;
+        (
+            Some(Bound::at_date(monday, zone)?),
+            Some(Bound::at_date(next_monday, zone)?),
+        )
+    } else if let Some(month) = args.month.as_deref() {
+        let first = parse_month(month)?;
+        let next = first
+            .checked_add_months(Months::new(1))
+            .ok_or_else(|| anyhow!("date overflow"))?;
+        (
+            Some(Bound::at_date(first, zone)?),
+            Some(Bound::at_date(next, zone)?),
+        )
+    } else if let Some(upcoming) = args.upcoming.as_deref() {
+        let span = parse_duration(upcoming)
+            .with_context(|| format!("failed to parse duration '{upcoming}'"))?;
+        let span = chrono::Duration::from_std(span)
+            .map_err(|_| anyhow!("duration '{upcoming}' is too large"))?;
+        let now = Utc::now();
+        let end = now
+            .checked_add_signed(span)
+            .ok_or_else(|| anyhow!("duration '{upcoming}' is too large"))?;
+        (
+            Some(Bound::starting_at(now, zone)),
+            Some(Bound::ending_at(end, zone)?),
+        )
+    } else if args.from.is_some() || args.to.is_some() {
+        let start = args
+            .from
+            .as_deref()
+            .map(|value| parse_from(value, zone))
+            .transpose()?;
+        let end = args
+            .to
+            .as_deref()
+            .map(|value| parse_to(value, zone))
+            .transpose()?;
+        if let (Some(start), Some(end)) = (&start, &end)
+            && end.instant <= start.instant
+        {
+            return Err(anyhow!("--to must be later than --from"));
+        }
+        (start, end)
+    } else {
+        return Ok(None);
+    };
+
+    window(start, end).map(Some)
+}
+
+/// The window covering the dates `start..end` (end exclusive) in `zone`.
+pub(super) fn date_range(
+    start: NaiveDate,
+    end: NaiveDate,
+    zone: &DisplayZone,
+) -> Result<EventRange> {
+    window(
+        Some(Bound::at_date(start, zone)?),
+        Some(Bound::at_date(end, zone)?),
+    )
+}
+
+fn window(start: Option<Bound>, end: Option<Bound>) -> Result<EventRange> {
+    let (starts_at, all_day_starts_at) = match start {
+        Some(bound) => bound.timestamps()?,
+        None => (EARLIEST.to_string(), EARLIEST.to_string()),
+    };
+    let (ends_at, all_day_ends_at) = match end {
+        Some(bound) => bound.timestamps()?,
+        None => (LATEST.to_string(), LATEST.to_string()),
+    };
+    Ok(EventRange {
+        starts_at,
+        ends_at,
+        all_day_starts_at,
+        all_day_ends_at,
+    })
+}
+
+/// Combines a resolved window with the tag flags.
+pub(super) fn event_filter(range: Option<EventRange>, args: &TagArgs) -> EventFilter {
+    EventFilter {
+        range,
+        tags: normalize_tags(&args.tags),
+        all_tags: args.all_tags,
+        exclude_tags: normalize_tags(&args.exclude_tags),
+        query: None,
+    }
+}
+
+fn normalize_tags(tags: &[String]) -> Vec<String> {
+    let mut tags: Vec<String> = tags
+        .iter()
+        .map(|tag| tag.trim().to_lowercase())
+        .filter(|tag| !tag.is_empty())
+        .collect();
+    tags.sort();
+    tags.dedup();
+    tags
+}
+
+fn parse_from(value: &str, zone: &DisplayZone) -> Result<Bound> {
+    if let Ok(instant) = DateTime::parse_from_rfc3339(value) {
+        return Ok(Bound::starting_at(instant.with_timezone(&Utc), zone));
+    }
+    Bound::at_date(parse_date(value)?, zone)
+}
+
+/// A date given to `--to` includes that whole day.
+fn parse_to(value: &str, zone: &DisplayZone) -> Result<Bound> {
+    if let Ok(instant) = DateTime::parse_from_rfc3339(value) {
+        return Bound::ending_at(instant.with_timezone(&Utc), zone);
+    }
+    Bound::at_date(next_day(parse_date(value)?)?, zone)
+}
+
+pub(super) fn parse_month(value: &str) -> Result<NaiveDate> {
+    if let Ok(first) = NaiveDate::parse_from_str(&format!("{}-01", value.trim()), "%Y-%m-%d") {
+        return Ok(first);
+    }
+    let date = parse_date(value)
+        .map_err(|_| anyhow!("expected YYYY-MM month (or a date), got '{value}'"))?;
+    date.with_day(1).ok_or_else(|| anyhow!("invalid month"))
+}
+
+fn next_day(date: NaiveDate) -> Result<NaiveDate> {
+    date.succ_opt().ok_or_else(|| anyhow!("date overflow"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn range(start: &str, end: &str, all_day_start: &str, all_day_end: &str) -> EventRange {
+        EventRange {
+            starts_at: start.into(),
+            ends_at: end.into(),
+            all_day_starts_at: all_day_start.into(),
+            all_day_ends_at: all_day_end.into(),
+        }
+    }
+
+    fn utc() -> DisplayZone {
+        DisplayZone::Named(chrono_tz::UTC)
+    }
+
+    #[test]
+    fn week_and_month_cover_whole_periods() {
+        let week = RangeArgs {
+            week: Some("2025-08-13".into()),
+            ..RangeArgs::default()
+        };
+        assert_eq!(
+            resolve_range(&week, &utc()).unwrap(),
+            Some(range(
+                "2025-08-11T00:00:00+00:00",
+                "2025-08-18T00:00:00+00:00",
+                "2025-08-11T00:00:00+00:00",
+                "2025-08-18T00:00:00+00:00",
+            ))
+        );
+
+        let month = RangeArgs {
+            month: Some("2025-12".into()),
+            ..RangeArgs::default()
+        };
+        assert_eq!(
+            resolve_range(&month, &utc()).unwrap(),
+            Some(range(
+                "2025-12-01T00:00:00+00:00",
+                "2026-01-01T00:00:00+00:00",
+                "2025-12-01T00:00:00+00:00",
+                "2026-01-01T00:00:00+00:00",
+            ))
+        );
+    }
+
+    #[test]
+    fn day_boundaries_follow_the_display_zone() {
+        let tokyo = DisplayZone::Named(chrono_tz::Asia::Tokyo);
+        let day = RangeArgs {
+            day: Some("2025-08-10".into()),
+            ..RangeArgs::default()
+        };
+        assert_eq!(
+            resolve_range(&day, &tokyo).unwrap(),
+            Some(range(
+                "2025-08-09T15:00:00+00:00",
+                "2025-08-10T15:00:00+00:00",
+                "2025-08-10T00:00:00+00:00",
+                "2025-08-11T00:00:00+00:00",
+            ))
+        );
+
+        let utc_day = RangeArgs {
+            utc_day: true,
+            ..day
+        };
+        assert_eq!(
+            resolve_range(&utc_day, &tokyo).unwrap(),
+            Some(range(
+                "2025-08-10T00:00:00+00:00",
+                "2025-08-11T00:00:00+00:00",
+                "2025-08-10T00:00:00+00:00",
+                "2025-08-11T00:00:00+00:00",
+            ))
+        );
+    }
+
+    #[test]
+    fn from_to_is_inclusive_and_may_be_open() {
+        let bounded = RangeArgs {
+            from: Some("2025-08-01".into()),
+            to: Some("2025-08-14".into()),
+            ..RangeArgs::default()
+        };
+        assert_eq!(
+            resolve_range(&bounded, &utc()).unwrap(),
+            Some(range(
+                "2025-08-01T00:00:00+00:00",
+                "2025-08-15T00:00:00+00:00",
+                "2025-08-01T00:00:00+00:00",
+                "2025-08-15T00:00:00+00:00",
+            ))
+        );
+
+        let open = RangeArgs {
+            to: Some("2025-08-14T12:00:00+09:00".into()),
+            ..RangeArgs::default()
+        };
+        assert_eq!(
+            resolve_range(&open, &utc()).unwrap(),
+            Some(range(
+                EARLIEST,
+                "2025-08-14T03:00:00+00:00",
+                EARLIEST,
+                "2025-08-15T00:00:00+00:00",
+            ))
+        );
+
+        let backwards = RangeArgs {
+            from: Some("2025-08-14".into()),
+            to: Some("2025-08-01".into()),
+            ..RangeArgs::default()
+        };
+        assert!(resolve_range(&backwards, &utc()).is_err());
+    }
+}
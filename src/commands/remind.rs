@@ -0,0 +1,265 @@
+use std::{process::Command as Process, thread};
+
+use anyhow::{Context, Result, anyhow};
+use chrono::{DateTime, Days, Duration, Utc};
+use humantime::parse_duration;
+
+use crate::{
+    cli::{RemindCommand, TagArgs},
+    storage::{Storage, StoredEvent},
+};
+
+use super::{
+    events::{DisplayZone, describe_reminder, parse_reminder, parse_timezone, parse_utc},
+    range::{date_range, event_filter},
+};
+
+const DEFAULT_INTERVAL: &str = "30s";
+/// How far ahead events are looked up; reminders set further before their
+/// event than this go out late.
+const LOOKAHEAD_DAYS: u64 = 31;
+/// A reminder missed while nothing was checking still goes out until its
+/// event starts, or for this long after it was due if that is later.
+const GRACE_MINUTES: i64 = 5;
+/// Delivered reminders are remembered this long after their event started.
+const KEEP_FIRED_DAYS: i64 = 7;
+/// Placeholders a reminder command may use, each standing for the
+/// `TOKI_NOTE_*` variable of the same name.
+const PLACEHOLDERS: [&str; 10] = [
+    "id", "title", "start", "end", "location", "url", "note", "tags", "minutes", "all_day",
+];
+
+pub fn run_reminders(storage: &mut Storage, cmd: RemindCommand) -> Result<()> {
+    if !cmd.daemon && !cmd.check {
+        return Err(anyhow!(
+            "pass --daemon to keep running or --check to deliver due reminders once"
+        ));
+    }
+    let zone = parse_timezone(cmd.tz.as_deref())?;
+    let default_offset = cmd
+        .default_offset
+        .as_deref()
+        .map(parse_reminder)
+        .transpose()?;
+    let interval = cmd.interval.as_deref().unwrap_or(DEFAULT_INTERVAL);
+    let interval = parse_duration(interval)
+        .with_context(|| format!("failed to parse interval '{interval}'"))?;
+
+    if cmd.check {
+        return deliver_due(storage, cmd.command.as_deref(), default_offset, &zone);
+    }
+    loop {
+        // A bad pass (say, the database is locked) should not stop the daemon.
+        if let Err(err) = deliver_due(storage, cmd.command.as_deref(), default_offset, &zone) {
+            eprintln!("toki-note remind: {err:#}");
+        }
+        thread::sleep(interval);
+    }
+}
+
+struct DueReminder<'a> {
+    event: &'a StoredEvent,
+    minutes: i64,
+    starts: DateTime<Utc>,
+}
+
+fn deliver_due(
+    storage: &mut Storage,
+    command: Option<&str>,
+    default_offset: Option<i64>,
+    zone: &DisplayZone,
+) -> Result<()> {
+    let now = Utc::now();
+    let today = zone.date_of(now);
+    let range = date_range(
+        today - Days::new(1),
+        today + Days::new(LOOKAHEAD_DAYS),
+        zone,
+    )?;
+    let events = storage.fetch_events(&event_filter(Some(range), &TagArgs::default()))?;
+
+    for reminder in due_reminders(&events, default_offset, zone, now)? {
+        let event = reminder.event;
+        if !storage.claim_alarm(event.id, &event.starts_at, reminder.minutes)? {
+            continue;
+        }
+        // The reminder stays recorded even if the command fails, so a broken
+        // command does not repeat it every pass.
+        if let Err(err) = notify(command, &reminder, zone) {
+            eprintln!(
+                "Reminder for #{} '{}' failed: {err:#}",
+                event.id, event.title
+            );
+        }
+    }
+
+    let forget_before = now - Duration::days(KEEP_FIRED_DAYS);
+    storage.prune_fired_alarms(&forget_before.to_rfc3339())?;
+    Ok(())
+}
+
+/// The reminders of `events` due at `now`. Events without reminders of their
+/// own get `default_offset`; cancelled events get none.
+fn due_reminders<'a>(
+    events: &'a [StoredEvent],
+    default_offset: Option<i64>,
+    zone: &DisplayZone,
+    now: DateTime<Utc>,
+) -> Result<Vec<DueReminder<'a>>> {
+    let mut due = Vec::new();
+    for event in events {
+        if event.is_cancelled() {
+            continue;
+        }
+        let offsets = if event.alarms.is_empty() {
+            default_offset.into_iter().collect()
+        } else {
+            event.alarms.clone()
+        };
+        if offsets.is_empty() {
+            continue;
+        }
+        let starts = occurrence_start(event, zone)?;
+        for minutes in offsets {
+            let fires_at = starts - Duration::minutes(minutes);
+            let until = starts.max(fires_at + Duration::minutes(GRACE_MINUTES));
+            if fires_at <= now && now < until {
+                due.push(DueReminder {
+                    event,
+                    minutes,
+                    starts,
+                });
+            }
+        }
+    }
+    Ok(due)
+}
+
+/// All-day events start at midnight in `zone` rather than in UTC.
+fn occurrence_start(event: &StoredEvent, zone: &DisplayZone) -> Result<DateTime<Utc>> {
+    let starts = parse_utc(&event.starts_at)?;
+    if event.all_day {
+        zone.midnight(starts.date_naive())
+    } else {
+        Ok(starts)
+    }
+}
+
+fn notify(command: Option<&str>, reminder: &DueReminder, zone: &DisplayZone) -> Result<()> {
+    let event = reminder.event;
+    let (start, end) = if event.all_day {
+        (
+            event.starts_at[..10].to_string(),
+            event.ends_at[..10].to_string(),
+        )
+    } else {
+        let spec = "%Y-%m-%d %H:%M";
+        (
+            zone.format(reminder.starts, spec),
+            zone.format(parse_utc(&event.ends_at)?, spec),
+        )
+    };
+    let Some(command) = command else {
+        println!(
+            "Reminder: #{} {} at {} ({})",
+            event.id,
+            event.title,
+            start,
+            describe_reminder(reminder.minutes)
+        );
+        return Ok(());
+    };
+
+    let values = [
+        event.id.to_string(),
+        event.title.clone(),
+        start,
+        end,
+        event.location.clone().unwrap_or_default(),
+        event.url.clone().unwrap_or_default(),
+        event.note.clone(),
+        event.tags.join(","),
+        reminder.minutes.to_string(),
+        event.all_day.to_string(),
+    ];
+    let mut process = Process::new("sh");
+    process.arg("-c").arg(expand_placeholders(command));
+    for (name, value) in PLACEHOLDERS.iter().zip(values) {
+        process.env(env_name(name), value);
+    }
+    let status = process
+        .status()
+        .with_context(|| format!("failed to run '{command}'"))?;
+    if !status.success() {
+        return Err(anyhow!("'{command}' exited with {status}"));
+    }
+    Ok(())
+}
+
+fn env_name(placeholder: &str) -> String {
+    format!("TOKI_NOTE_{}", placeholder.to_uppercase())
+}
+
+/// Swaps `{title}` and friends for variable references so event text reaches
+/// the shell as data and is never parsed as part of the command.
+fn expand_placeholders(command: &str) -> String {
+    PLACEHOLDERS
+        .iter()
+        .fold(command.to_string(), |command, name| {
+            command.replace(&format!("{{{name}}}"), &format!("${{{}}}", env_name(name)))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::EventStatus;
+
+    fn event(id: i64, start: &str, all_day: bool, alarms: Vec<i64>) -> StoredEvent {
+        StoredEvent {
+            id,
+            title: "Standup".into(),
+            starts_at: start.into(),
+            ends_at: start.into(),
+            all_day,
+            alarms,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn picks_reminders_that_are_due() {
+        let tokyo = DisplayZone::Named(chrono_tz::Asia::Tokyo);
+        let now = parse_utc("2025-08-14T00:55:00+00:00").unwrap();
+        let mut cancelled = event(5, "2025-08-14T01:00:00+00:00", false, vec![10]);
+        cancelled.status = Some(EventStatus::Cancelled);
+        let events = vec![
+            // Due 10 minutes before; the hour-before one was missed but the
+            // event has not started yet.
+            event(1, "2025-08-14T01:00:00+00:00", false, vec![60, 10, 2]),
+            // No reminders of its own, so the default applies.
+            event(2, "2025-08-14T01:00:00+00:00", false, Vec::new()),
+            // Started three minutes ago, still within the grace period.
+            event(3, "2025-08-14T00:52:00+00:00", false, vec![0]),
+            // Started ten minutes ago; too late.
+            event(4, "2025-08-14T00:45:00+00:00", false, vec![0]),
+            // An all-day event that began at midnight Tokyo time, 10 hours ago.
+            event(6, "2025-08-14T00:00:00+00:00", true, vec![0]),
+            cancelled,
+        ];
+        let due: Vec<_> = due_reminders(&events, Some(30), &tokyo, now)
+            .unwrap()
+            .iter()
+            .map(|reminder| (reminder.event.id, reminder.minutes))
+            .collect();
+        assert_eq!(due, vec![(1, 60), (1, 10), (2, 30), (3, 0)]);
+    }
+
+    #[test]
+    fn placeholders_become_variables() {
+        assert_eq!(
+            expand_placeholders(r#"notify-send "{title}" "{start} {unknown}""#),
+            r#"notify-send "${TOKI_NOTE_TITLE}" "${TOKI_NOTE_START} {unknown}""#
+        );
+    }
+}
@@ -0,0 +1,41 @@
+use anyhow::{Result, anyhow};
+
+use crate::{cli::SearchCommand, storage::Storage};
+
+use super::{
+    events::{format_event_timing, parse_timezone},
+    range::{event_filter, resolve_range},
+};
+
+pub fn search_events(storage: &Storage, cmd: SearchCommand) -> Result<()> {
+    let query = cmd.query.trim();
+    if query.is_empty() {
+        return Err(anyhow!("search query must not be empty"));
+    }
+    let tz = parse_timezone(cmd.tz.as_deref())?;
+    let range = resolve_range(&cmd.range, &tz)?;
+    let hits = storage.search(query, &event_filter(range, &cmd.tags), cmd.limit)?;
+
+    if hits.is_empty() {
+        println!("No events match '{query}'");
+        return Ok(());
+    }
+
+    for hit in hits {
+        let timing = format_event_timing(&hit.event, &tz)?;
+        println!("#{} {}", hit.event.id, hit.title);
+        println!("  {timing}");
+        if let Some(rule) = &hit.event.recurrence {
+            println!("  repeats: {}", rule.describe());
+        }
+        if !hit.event.tags.is_empty() {
+            println!("  tags: {}", hit.event.tags.join(", "));
+        }
+        if !hit.snippet.is_empty() {
+            println!("  note: {}", hit.snippet.replace('\n', " "));
+        }
+        println!();
+    }
+
+    Ok(())
+}
@@ -0,0 +1,324 @@
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Duration, Utc, format::Item, format::StrftimeItems};
+
+use crate::storage::StoredEvent;
+
+use super::events::{DisplayZone, describe_reminder, format_event_timing, parse_utc};
+
+/// The layout `list` prints when no template is given.
+pub(super) const DEFAULT_LIST_TEMPLATE: &str = "#{id} {title}{?status} [{status}]{/status}{?private} (private){/private}\n  {timing}\n\
+     {?location}  location: {location}\n{/location}\
+     {?url}  url: {url}\n{/url}\
+     {?repeats}  repeats: {repeats}\n{/repeats}\
+     {?tags}  tags: {tags}\n{/tags}\
+     {?reminders}  reminders: {reminders}\n{/reminders}\
+     {?note}  note: {note}\n{/note}";
+
+const FIELDS: &[&str] = &[
+    "id",
+    "uid",
+    "title",
+    "start",
+    "end",
+    "all_day",
+    "private",
+    "location",
+    "url",
+    "status",
+    "tags",
+    "reminders",
+    "note",
+    "repeats",
+    "timing",
+    "zone",
+];
+
+/// A parsed output template.
+///
+/// `{field}` inserts a field and `{start:%H:%M}` formats a time with
+/// strftime-style specifiers in the display zone (for `tags` the spec is
+/// the separator, `, ` by default). `{?field}...{/field}` keeps its body
+/// only when the field is non-empty (or true), `{!field}...{/field}` only
+/// when it is not. `{{`, `}}`, `\n`, `\t` and `\\` are escapes.
+#[derive(Debug)]
+pub(super) struct Template {
+    segments: Vec<Segment>,
+}
+
+#[derive(Debug)]
+enum Segment {
+    Literal(String),
+    Field {
+        name: String,
+        spec: Option<String>,
+    },
+    Section {
+        name: String,
+        inverted: bool,
+        body: Vec<Segment>,
+    },
+}
+
+enum Value {
+    Text(String),
+    Bool(bool),
+    List(Vec<String>),
+    Time(DateTime<Utc>),
+    /// A calendar date, used for all-day starts and (inclusive) ends.
+    Date(DateTime<Utc>),
+}
+
+impl Template {
+    pub(super) fn parse(source: &str) -> Result<Self> {
+        let mut stack: Vec<(String, bool, Vec<Segment>)> = Vec::new();
+        let mut segments = Vec::new();
+        let mut literal = String::new();
+        let mut chars = source.chars().peekable();
+
+        while let Some(ch) = chars.next() {
+            match ch {
+                '\\' => match chars.next() {
+                    Some('n') => literal.push('\n'),
+                    Some('t') => literal.push('\t'),
+                    Some(other) => literal.push(other),
+                    None => literal.push('\\'),
+                },
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    literal.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    literal.push('}');
+                }
+                '}' => return Err(anyhow!("unmatched '}}' in template; write '}}}}'")),
+                '{' => {
+                    let mut tag = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(c) => tag.push(c),
+                            None => return Err(anyhow!("unclosed '{{{tag}' in template")),
+                        }
+                    }
+                    if !literal.is_empty() {
+                        segments.push(Segment::Literal(std::mem::take(&mut literal)));
+                    }
+                    if let Some(name) = tag.strip_prefix('/') {
+                        let (open, inverted, outer) = stack
+                            .pop()
+                            .ok_or_else(|| anyhow!("'{{/{name}}}' closes no section"))?;
+                        if open != name {
+                            return Err(anyhow!(
+                                "'{{/{name}}}' closes the '{open}' section; expected '{{/{open}}}'"
+                            ));
+                        }
+                        let body = std::mem::replace(&mut segments, outer);
+                        segments.push(Segment::Section {
+                            name: open,
+                            inverted,
+                            body,
+                        });
+                    } else if let Some((inverted, name)) = tag
+                        .strip_prefix('?')
+                        .map(|name| (false, name))
+                        .or_else(|| tag.strip_prefix('!').map(|name| (true, name)))
+                    {
+                        check_field(name)?;
+                        stack.push((name.to_string(), inverted, std::mem::take(&mut segments)));
+                    } else {
+                        let (name, spec) = match tag.split_once(':') {
+                            Some((name, spec)) => (name, Some(spec.to_string())),
+                            None => (tag.as_str(), None),
+                        };
+                        check_field(name)?;
+                        if let Some(spec) = &spec
+                            && matches!(name, "start" | "end")
+                            && StrftimeItems::new(spec).any(|item| matches!(item, Item::Error))
+                        {
+                            return Err(anyhow!("invalid time format '{spec}' for {{{name}}}"));
+                        }
+                        segments.push(Segment::Field {
+                            name: name.to_string(),
+                            spec,
+                        });
+                    }
+                }
+                other => literal.push(other),
+            }
+        }
+        if let Some((name, _, _)) = stack.pop() {
+            return Err(anyhow!(
+                "section '{name}' is never closed with '{{/{name}}}'"
+            ));
+        }
+        if !literal.is_empty() {
+            segments.push(Segment::Literal(literal));
+        }
+        Ok(Self { segments })
+    }
+
+    pub(super) fn render(&self, event: &StoredEvent, zone: &DisplayZone) -> Result<String> {
+        let mut out = String::new();
+        render_segments(&self.segments, event, zone, &mut out)?;
+        Ok(out)
+    }
+}
+
+fn check_field(name: &str) -> Result<()> {
+    if FIELDS.contains(&name) {
+        Ok(())
+    } else {
+        Err(anyhow!(
+            "unknown template field '{name}'; expected one of {}",
+            FIELDS.join(", ")
+        ))
+    }
+}
+
+fn render_segments(
+    segments: &[Segment],
+    event: &StoredEvent,
+    zone: &DisplayZone,
+    out: &mut String,
+) -> Result<()> {
+    for segment in segments {
+        match segment {
+            Segment::Literal(text) => out.push_str(text),
+            Segment::Field { name, spec } => {
+                let value = field_value(name, event, zone)?;
+                out.push_str(&format_value(value, spec.as_deref(), zone));
+            }
+            Segment::Section {
+                name,
+                inverted,
+                body,
+            } => {
+                if is_truthy(&field_value(name, event, zone)?) != *inverted {
+                    render_segments(body, event, zone, out)?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn field_value(name: &str, event: &StoredEvent, zone: &DisplayZone) -> Result<Value> {
+    Ok(match name {
+        "id" => Value::Text(event.id.to_string()),
+        "uid" => Value::Text(event.uid.clone().unwrap_or_default()),
+        "title" => Value::Text(event.title.clone()),
+        "note" => Value::Text(event.note.clone()),
+        "tags" => Value::List(event.tags.clone()),
+        "reminders" => Value::List(
+            event
+                .alarms
+                .iter()
+                .map(|minutes| describe_reminder(*minutes))
+                .collect(),
+        ),
+        "all_day" => Value::Bool(event.all_day),
+        "private" => Value::Bool(event.private),
+        "location" => Value::Text(event.location.clone().unwrap_or_default()),
+        "url" => Value::Text(event.url.clone().unwrap_or_default()),
+        "status" => Value::Text(
+            event
+                .status
+                .map(|status| status.to_string())
+                .unwrap_or_default(),
+        ),
+        "repeats" => Value::Text(
+            event
+                .recurrence
+                .as_ref()
+                .map(|rule| rule.describe())
+                .unwrap_or_default(),
+        ),
+        "timing" => Value::Text(format_event_timing(event, zone)?),
+        "zone" => Value::Text(zone.name()),
+        "start" if event.all_day => Value::Date(parse_utc(&event.starts_at)?),
+        "start" => Value::Time(parse_utc(&event.starts_at)?),
+        "end" if event.all_day => {
+            // Stored ends are exclusive; show the last day like `list` does.
+            let start = parse_utc(&event.starts_at)?;
+            let end = parse_utc(&event.ends_at)?;
+            Value::Date((end - Duration::days(1)).max(start))
+        }
+        "end" => Value::Time(parse_utc(&event.ends_at)?),
+        other => return Err(anyhow!("unknown template field '{other}'")),
+    })
+}
+
+fn format_value(value: Value, spec: Option<&str>, zone: &DisplayZone) -> String {
+    match value {
+        Value::Text(text) => text,
+        Value::Bool(flag) => flag.to_string(),
+        Value::List(items) => items.join(spec.unwrap_or(", ")),
+        Value::Time(instant) => zone.format(instant, spec.unwrap_or("%Y-%m-%d %H:%M")),
+        // All-day events are dates, not instants; format them as stored.
+        Value::Date(date) => date.format(spec.unwrap_or("%Y-%m-%d")).to_string(),
+    }
+}
+
+fn is_truthy(value: &Value) -> bool {
+    match value {
+        Value::Text(text) => !text.is_empty(),
+        Value::Bool(flag) => *flag,
+        Value::List(items) => !items.is_empty(),
+        Value::Time(_) | Value::Date(_) => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> StoredEvent {
+        StoredEvent {
+            id: 3,
+            title: "Sync".into(),
+            starts_at: "2025-08-09T23:00:00+00:00".into(),
+            ends_at: "2025-08-10T00:00:00+00:00".into(),
+            tags: vec!["work".into(), "team".into()],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn renders_fields_formats_and_sections() {
+        let tokyo = DisplayZone::Named(chrono_tz::Asia::Tokyo);
+        let template =
+            Template::parse("{start:%H:%M}-{end:%H:%M} {title} [{tags:|}]{?note} ({note}){/note}")
+                .unwrap();
+        assert_eq!(
+            template.render(&sample(), &tokyo).unwrap(),
+            "08:00-09:00 Sync [work|team]"
+        );
+
+        let mut event = sample();
+        event.note = "bring slides".into();
+        event.all_day = true;
+        let template = Template::parse(
+            "{{{!all_day}{start:%H:%M}{/all_day}{?all_day}{start}{/all_day}}}\\t{note}",
+        )
+        .unwrap();
+        assert_eq!(
+            template.render(&event, &tokyo).unwrap(),
+            "{2025-08-09}\tbring slides"
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_templates() {
+        for source in [
+            "{titel}",
+            "{title",
+            "{?note}open",
+            "{?note}x{/tags}",
+            "{start:%Q}",
+            "oops}",
+        ] {
+            assert!(Template::parse(source).is_err(), "{source} should fail");
+        }
+    }
+}
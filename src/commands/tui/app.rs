@@ -0,0 +1,657 @@
+use anyhow::{Context, Result, anyhow};
+use chrono::{Datelike, Days, Duration, Months, NaiveDate, NaiveTime, Utc, Weekday};
+use humantime::parse_duration;
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::{
+    cli::TagArgs,
+    storage::{EventUpdate, NewEvent, Storage, StoredEvent},
+};
+
+use crate::commands::{
+    calendar::{event_days, week_containing},
+    events::{DisplayZone, EventTiming, occurrence_copy, parse_date, parse_utc, utc_midnight},
+    range::{date_range, event_filter},
+};
+
+/// Length of an event added with only a start time, as with `add`.
+const DEFAULT_DURATION: Duration = Duration::minutes(30);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum View {
+    Month,
+    Week,
+    Day,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum PromptAction {
+    Add,
+    Move,
+    Title,
+    Note,
+    Tags,
+}
+
+impl PromptAction {
+    pub(super) fn label(self) -> &'static str {
+        match self {
+            PromptAction::Add => "add",
+            PromptAction::Move => "move to",
+            PromptAction::Title => "title",
+            PromptAction::Note => "note",
+            PromptAction::Tags => "tags",
+        }
+    }
+
+    pub(super) fn hint(self) -> &'static str {
+        match self {
+            PromptAction::Add => "HH:MM[-HH:MM] title, or just a title for an all-day event",
+            PromptAction::Move => "HH:MM, a date, both, or a shift such as +1d or -30m",
+            PromptAction::Title | PromptAction::Note => "Enter saves, Esc cancels",
+            PromptAction::Tags => "comma-separated; replaces every tag",
+        }
+    }
+}
+
+pub(super) enum Mode {
+    Normal,
+    Prompt {
+        action: PromptAction,
+        input: String,
+    },
+    /// Waiting for the user to confirm deleting this event.
+    Confirm(Box<StoredEvent>),
+}
+
+/// An event with the days it covers in the display zone.
+pub(super) struct Entry {
+    pub(super) event: StoredEvent,
+    pub(super) first: NaiveDate,
+    pub(super) last: NaiveDate,
+}
+
+impl Entry {
+    pub(super) fn covers(&self, date: NaiveDate) -> bool {
+        self.first <= date && date <= self.last
+    }
+}
+
+pub(super) struct App {
+    pub(super) zone: DisplayZone,
+    pub(super) week_start: Weekday,
+    pub(super) view: View,
+    pub(super) selected: NaiveDate,
+    /// Index of the highlighted event among the selected day's events.
+    pub(super) cursor: usize,
+    pub(super) entries: Vec<Entry>,
+    pub(super) mode: Mode,
+    pub(super) status: Option<String>,
+    pub(super) quit: bool,
+    tags: TagArgs,
+    /// Dates the entries were loaded for (end exclusive).
+    loaded: Option<(NaiveDate, NaiveDate)>,
+    /// `Storage::data_version` at the last load.
+    version: Option<i64>,
+}
+
+impl App {
+    pub(super) fn new(
+        zone: DisplayZone,
+        week_start: Weekday,
+        selected: NaiveDate,
+        tags: TagArgs,
+    ) -> Self {
+        Self {
+            zone,
+            week_start,
+            view: View::Month,
+            selected,
+            cursor: 0,
+            entries: Vec::new(),
+            mode: Mode::Normal,
+            status: None,
+            quit: false,
+            tags,
+            loaded: None,
+            version: None,
+        }
+    }
+
+    pub(super) fn today(&self) -> NaiveDate {
+        self.zone.date_of(Utc::now())
+    }
+
+    /// First day of the month grid around the selected date; the grid always
+    /// spans six weeks so the week and day views stay inside it.
+    pub(super) fn grid_start(&self) -> NaiveDate {
+        let first = self.selected.with_day(1).unwrap_or(self.selected);
+        week_containing(first, self.week_start).unwrap_or(first)
+    }
+
+    pub(super) fn week_start_date(&self) -> NaiveDate {
+        week_containing(self.selected, self.week_start).unwrap_or(self.selected)
+    }
+
+    fn grid_dates(&self) -> (NaiveDate, NaiveDate) {
+        let start = self.grid_start();
+        (start, start + Days::new(42))
+    }
+
+    pub(super) fn day_entries(&self, date: NaiveDate) -> impl Iterator<Item = &Entry> {
+        self.entries.iter().filter(move |entry| entry.covers(date))
+    }
+
+    pub(super) fn selected_event(&self) -> Option<&StoredEvent> {
+        self.day_entries(self.selected)
+            .nth(self.cursor)
+            .map(|entry| &entry.event)
+    }
+
+    /// Reloads the events around the selected date.
+    pub(super) fn refresh(&mut self, storage: &Storage) -> Result<()> {
+        let (start, end) = self.grid_dates();
+        let range = date_range(start, end, &self.zone)?;
+        let mut entries = storage
+            .fetch_events(&event_filter(Some(range), &self.tags))?
+            .into_iter()
+            .map(|event| {
+                let (first, last) = event_days(&event, &self.zone)?;
+                Ok(Entry { event, first, last })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        entries.sort_by(|a, b| {
+            (!a.event.all_day, &a.event.starts_at, a.event.id).cmp(&(
+                !b.event.all_day,
+                &b.event.starts_at,
+                b.event.id,
+            ))
+        });
+        self.entries = entries;
+        self.loaded = Some((start, end));
+        self.version = Some(storage.data_version()?);
+        self.clamp_cursor();
+        Ok(())
+    }
+
+    /// Reloads when another process has written to the database since the
+    /// last load.
+    pub(super) fn poll_changes(&mut self, storage: &Storage) -> Result<()> {
+        if self.version != Some(storage.data_version()?) {
+            self.refresh(storage)?;
+            if matches!(self.mode, Mode::Normal) {
+                self.status = Some("Reloaded: the database changed".to_string());
+            }
+        }
+        Ok(())
+    }
+
+    pub(super) fn handle_key(&mut self, key: KeyEvent, storage: &mut Storage) -> Result<()> {
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            self.quit = true;
+            return Ok(());
+        }
+        match std::mem::replace(&mut self.mode, Mode::Normal) {
+            Mode::Normal => self.handle_normal(key, storage),
+            Mode::Prompt { action, mut input } => match key.code {
+                KeyCode::Enter => {
+                    let result = self.submit(action, input.trim(), storage);
+                    if result.is_err() {
+                        // Keep the input so it can be corrected.
+                        self.mode = Mode::Prompt { action, input };
+                    }
+                    result
+                }
+                KeyCode::Esc => Ok(()),
+                KeyCode::Backspace => {
+                    input.pop();
+                    self.mode = Mode::Prompt { action, input };
+                    Ok(())
+                }
+                KeyCode::Char(ch) => {
+                    input.push(ch);
+                    self.mode = Mode::Prompt { action, input };
+                    Ok(())
+                }
+                _ => {
+                    self.mode = Mode::Prompt { action, input };
+                    Ok(())
+                }
+            },
+            Mode::Confirm(event) => match key.code {
+                KeyCode::Char('y') => self.delete(&event, false, storage),
+                KeyCode::Char('s') if event.recurrence.is_some() || event.parent_id.is_some() => {
+                    self.delete(&event, true, storage)
+                }
+                KeyCode::Char('n') | KeyCode::Esc => Ok(()),
+                _ => {
+                    self.mode = Mode::Confirm(event);
+                    Ok(())
+                }
+            },
+        }
+    }
+
+    fn handle_normal(&mut self, key: KeyEvent, storage: &mut Storage) -> Result<()> {
+        self.status = None;
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => self.quit = true,
+            KeyCode::Left | KeyCode::Char('h') => self.select(self.selected - Days::new(1)),
+            KeyCode::Right | KeyCode::Char('l') => self.select(self.selected + Days::new(1)),
+            KeyCode::Char('K') => self.select(self.selected - Days::new(7)),
+            KeyCode::Char('J') => self.select(self.selected + Days::new(7)),
+            KeyCode::PageUp | KeyCode::Char('[') => self.select(self.step_period(false)),
+            KeyCode::PageDown | KeyCode::Char(']') => self.select(self.step_period(true)),
+            KeyCode::Char('t') => self.select(self.today()),
+            KeyCode::Up | KeyCode::Char('k') => self.cursor = self.cursor.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.cursor += 1;
+                self.clamp_cursor();
+            }
+            KeyCode::Char('m') => self.view = View::Month,
+            KeyCode::Char('w') => self.view = View::Week,
+            KeyCode::Char('d') => self.view = View::Day,
+            KeyCode::Tab => {
+                self.view = match self.view {
+                    View::Month => View::Week,
+                    View::Week => View::Day,
+                    View::Day => View::Month,
+                }
+            }
+            KeyCode::Char('r') => {
+                self.refresh(storage)?;
+                self.status = Some("Reloaded".to_string());
+            }
+            KeyCode::Char('a') => self.prompt(PromptAction::Add, String::new()),
+            KeyCode::Char('v') => {
+                let event = self.require_selected()?;
+                let start = parse_utc(&event.starts_at)?;
+                let current = if event.all_day {
+                    start.format("%Y-%m-%d").to_string()
+                } else {
+                    self.zone.format(start, "%H:%M")
+                };
+                self.prompt(PromptAction::Move, current);
+            }
+            KeyCode::Char('e') => {
+                let title = self.require_selected()?.title.clone();
+                self.prompt(PromptAction::Title, title);
+            }
+            KeyCode::Char('n') => {
+                let note = self.require_selected()?.note.clone();
+                self.prompt(PromptAction::Note, note);
+            }
+            KeyCode::Char('g') => {
+                let tags = self.require_selected()?.tags.join(", ");
+                self.prompt(PromptAction::Tags, tags);
+            }
+            KeyCode::Char('x') | KeyCode::Delete => {
+                self.mode = Mode::Confirm(Box::new(self.require_selected()?.clone()));
+            }
+            _ => {}
+        }
+        if self.loaded != Some(self.grid_dates()) {
+            self.refresh(storage)?;
+        }
+        Ok(())
+    }
+
+    fn select(&mut self, date: NaiveDate) {
+        if date != self.selected {
+            self.selected = date;
+            self.cursor = 0;
+        }
+    }
+
+    /// The selected date moved by one month, week or day depending on the view.
+    fn step_period(&self, forward: bool) -> NaiveDate {
+        let date = self.selected;
+        let stepped = match (self.view, forward) {
+            (View::Month, true) => date.checked_add_months(Months::new(1)),
+            (View::Month, false) => date.checked_sub_months(Months::new(1)),
+            (View::Week, true) => date.checked_add_days(Days::new(7)),
+            (View::Week, false) => date.checked_sub_days(Days::new(7)),
+            (View::Day, true) => date.succ_opt(),
+            (View::Day, false) => date.pred_opt(),
+        };
+        stepped.unwrap_or(date)
+    }
+
+    fn clamp_cursor(&mut self) {
+        let count = self.day_entries(self.selected).count();
+        self.cursor = self.cursor.min(count.saturating_sub(1));
+    }
+
+    fn prompt(&mut self, action: PromptAction, input: String) {
+        self.mode = Mode::Prompt { action, input };
+    }
+
+    fn require_selected(&self) -> Result<&StoredEvent> {
+        self.selected_event()
+            .ok_or_else(|| anyhow!("no event selected on {}", self.selected))
+    }
+
+    fn submit(&mut self, action: PromptAction, input: &str, storage: &mut Storage) -> Result<()> {
+        let (id, message) = match action {
+            PromptAction::Add => {
+                let id =
+                    storage.insert_event(parse_new_event(input, self.selected, &self.zone)?)?;
+                (id, format!("Stored event #{id}"))
+            }
+            PromptAction::Move => {
+                let event = self.require_selected()?.clone();
+                let timing = parse_move(input, &event, &self.zone)?;
+                let id = if event.recurrence.is_some() {
+                    storage.upsert_occurrence_override(
+                        event.id,
+                        &event.starts_at,
+                        occurrence_copy(&event, &timing),
+                    )?
+                } else {
+                    storage.update_event_timing(
+                        event.id,
+                        &timing.starts_at,
+                        &timing.ends_at,
+                        event.all_day,
+                    )?;
+                    event.id
+                };
+                self.selected = start_day(&timing, &event, &self.zone)?;
+                (id, format!("Moved event #{id}"))
+            }
+            PromptAction::Title | PromptAction::Note | PromptAction::Tags => {
+                let id = self.require_selected()?.id;
+                let update = match action {
+                    PromptAction::Title if input.is_empty() => {
+                        return Err(anyhow!("title must not be empty"));
+                    }
+                    PromptAction::Title => EventUpdate {
+                        title: Some(input.to_string()),
+                        ..EventUpdate::default()
+                    },
+                    PromptAction::Note => EventUpdate {
+                        note: Some(input.to_string()),
+                        ..EventUpdate::default()
+                    },
+                    _ => EventUpdate {
+                        clear_tags: true,
+                        add_tags: input
+                            .split(',')
+                            .map(str::trim)
+                            .filter(|tag| !tag.is_empty())
+                            .map(str::to_string)
+                            .collect(),
+                        ..EventUpdate::default()
+                    },
+                };
+                if !storage.update_event(id, &update)? {
+                    return Err(anyhow!("No event found with id {id}"));
+                }
+                (id, format!("Updated event #{id}"))
+            }
+        };
+        self.refresh(storage)?;
+        let index = self
+            .day_entries(self.selected)
+            .position(|entry| entry.event.id == id);
+        self.cursor = index.unwrap_or(self.cursor);
+        self.status = Some(message);
+        Ok(())
+    }
+
+    /// Deletes `event`. For an occurrence of a series this cancels just that
+    /// occurrence unless `series` asks for the whole series.
+    fn delete(&mut self, event: &StoredEvent, series: bool, storage: &mut Storage) -> Result<()> {
+        let date = self.selected;
+        let message = match (event.parent_id, event.recurrence.is_some()) {
+            (Some(parent), _) if series => {
+                storage.delete_by_id(parent)?;
+                format!("Deleted event #{parent} (every occurrence)")
+            }
+            (None, true) if series => {
+                storage.delete_by_id(event.id)?;
+                format!("Deleted event #{} (every occurrence)", event.id)
+            }
+            (Some(parent), _) => {
+                let original = event
+                    .recurrence_id
+                    .as_deref()
+                    .ok_or_else(|| anyhow!("override #{} has no recurrence id", event.id))?;
+                storage.add_exdate(parent, original)?;
+                format!("Cancelled the {date} occurrence of event #{parent}")
+            }
+            (None, true) => {
+                storage.add_exdate(event.id, &event.starts_at)?;
+                format!("Cancelled the {date} occurrence of event #{}", event.id)
+            }
+            (None, false) => {
+                storage.delete_by_id(event.id)?;
+                format!("Deleted event #{}", event.id)
+            }
+        };
+        self.refresh(storage)?;
+        self.status = Some(message);
+        Ok(())
+    }
+}
+
+/// The day a moved event now starts on, so the view follows it.
+fn start_day(timing: &EventTiming, event: &StoredEvent, zone: &DisplayZone) -> Result<NaiveDate> {
+    let start = parse_utc(&timing.starts_at)?;
+    Ok(if event.all_day {
+        start.date_naive()
+    } else {
+        zone.date_of(start)
+    })
+}
+
+/// Parses the add prompt: `09:00 Standup`, `09:00-10:30 Review` or, without
+/// a time, an all-day event on `date`.
+fn parse_new_event(input: &str, date: NaiveDate, zone: &DisplayZone) -> Result<NewEvent> {
+    let (first, rest) = input.split_once(' ').unwrap_or((input, ""));
+    let times = match first.split_once('-') {
+        Some((start, end)) => parse_time(start).zip(parse_time(end).map(Some)),
+        None => parse_time(first).map(|start| (start, None)),
+    };
+    let (title, timing) = match times {
+        Some((start, end)) => {
+            let starts_at = zone
+                .instant(date.and_time(start))
+                .ok_or_else(|| anyhow!("{date} {start} does not exist in the display timezone"))?;
+            let ends_at = match end {
+                Some(end) => {
+                    let day = if end <= start {
+                        date + Days::new(1)
+                    } else {
+                        date
+                    };
+                    zone.instant(day.and_time(end)).ok_or_else(|| {
+                        anyhow!("{day} {end} does not exist in the display timezone")
+                    })?
+                }
+                None => starts_at + DEFAULT_DURATION,
+            };
+            (rest.trim(), (starts_at, ends_at, false))
+        }
+        None => (
+            input,
+            (
+                utc_midnight(date)?,
+                utc_midnight(date + Days::new(1))?,
+                true,
+            ),
+        ),
+    };
+    if title.is_empty() {
+        return Err(anyhow!("give the event a title"));
+    }
+    let (starts_at, ends_at, all_day) = timing;
+    Ok(NewEvent {
+        title: title.to_string(),
+        starts_at: starts_at.to_rfc3339(),
+        ends_at: ends_at.to_rfc3339(),
+        all_day,
+        ..NewEvent::default()
+    })
+}
+
+/// Parses the move prompt into new timing that keeps the event's length:
+/// `+1d`/`-30m` shift it, `HH:MM` and a date (`2025-08-12`, `tomorrow`)
+/// set the time and day in the display zone.
+fn parse_move(input: &str, event: &StoredEvent, zone: &DisplayZone) -> Result<EventTiming> {
+    let start = parse_utc(&event.starts_at)?;
+    let span = parse_utc(&event.ends_at)?.signed_duration_since(start);
+
+    let new_start = if let Some((sign, amount)) = input
+        .strip_prefix('+')
+        .map(|amount| (1, amount))
+        .or_else(|| input.strip_prefix('-').map(|amount| (-1, amount)))
+    {
+        let shift = parse_duration(amount.trim())
+            .with_context(|| format!("failed to parse duration '{amount}'"))?;
+        let shift = Duration::from_std(shift)
+            .map_err(|_| anyhow!("duration '{amount}' is too large"))?
+            * sign;
+        if event.all_day && shift.num_seconds() % 86_400 != 0 {
+            return Err(anyhow!("all-day events move by whole days"));
+        }
+        start + shift
+    } else {
+        let mut date = None;
+        let mut time = None;
+        for part in input.split_whitespace() {
+            match parse_time(part) {
+                Some(value) => time = Some(value),
+                None => date = Some(parse_date(part)?),
+            }
+        }
+        if date.is_none() && time.is_none() {
+            return Err(anyhow!("enter a time, a date or a shift such as +1d"));
+        }
+        if event.all_day {
+            if time.is_some() {
+                return Err(anyhow!("all-day events move by date"));
+            }
+            utc_midnight(date.unwrap_or(start.date_naive()))?
+        } else {
+            let current = zone.naive(start);
+            let naive = date
+                .unwrap_or(current.date())
+                .and_time(time.unwrap_or(current.time()));
+            zone.instant(naive)
+                .ok_or_else(|| anyhow!("{naive} does not exist in the display timezone"))?
+        }
+    };
+    Ok(EventTiming {
+        starts_at: new_start.to_rfc3339(),
+        ends_at: (new_start + span).to_rfc3339(),
+    })
+}
+
+fn parse_time(value: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(value, "%H:%M").ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn utc() -> DisplayZone {
+        DisplayZone::Named(chrono_tz::UTC)
+    }
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap()
+    }
+
+    fn type_keys(app: &mut App, storage: &mut Storage, keys: &str) {
+        for ch in keys.chars() {
+            let code = match ch {
+                '\n' => KeyCode::Enter,
+                other => KeyCode::Char(other),
+            };
+            app.handle_key(KeyEvent::from(code), storage).unwrap();
+        }
+    }
+
+    #[test]
+    fn parses_add_and_move_prompts() {
+        let tokyo = DisplayZone::Named(chrono_tz::Asia::Tokyo);
+        let event = parse_new_event("23:30-00:30 Late call", date("2025-08-10"), &tokyo).unwrap();
+        assert_eq!(event.title, "Late call");
+        assert_eq!(event.starts_at, "2025-08-10T14:30:00+00:00");
+        assert_eq!(event.ends_at, "2025-08-10T15:30:00+00:00");
+        assert!(!event.all_day);
+
+        let trip = parse_new_event("Trip to 10:00 town", date("2025-08-10"), &tokyo).unwrap();
+        assert!(trip.all_day);
+        assert_eq!(trip.title, "Trip to 10:00 town");
+        assert!(parse_new_event("09:00", date("2025-08-10"), &tokyo).is_err());
+
+        let stored = StoredEvent {
+            id: 1,
+            title: "Sync".into(),
+            starts_at: "2025-08-10T00:00:00+00:00".into(),
+            ends_at: "2025-08-10T01:00:00+00:00".into(),
+            ..Default::default()
+        };
+        let moved = parse_move("14:00", &stored, &tokyo).unwrap();
+        assert_eq!(moved.starts_at, "2025-08-10T05:00:00+00:00");
+        assert_eq!(moved.ends_at, "2025-08-10T06:00:00+00:00");
+        let moved = parse_move("2025-08-12 10:00", &stored, &tokyo).unwrap();
+        assert_eq!(moved.starts_at, "2025-08-12T01:00:00+00:00");
+        let moved = parse_move("-90m", &stored, &tokyo).unwrap();
+        assert_eq!(moved.starts_at, "2025-08-09T22:30:00+00:00");
+
+        let all_day = StoredEvent {
+            all_day: true,
+            ends_at: "2025-08-11T00:00:00+00:00".into(),
+            ..stored
+        };
+        let moved = parse_move("+2d", &all_day, &utc()).unwrap();
+        assert_eq!(moved.starts_at, "2025-08-12T00:00:00+00:00");
+        assert_eq!(moved.ends_at, "2025-08-13T00:00:00+00:00");
+        assert!(parse_move("+3h", &all_day, &utc()).is_err());
+        assert!(parse_move("10:00", &all_day, &utc()).is_err());
+    }
+
+    #[test]
+    fn keys_add_edit_move_and_delete_events() {
+        let dir = tempdir().unwrap();
+        let mut storage = Storage::new(&dir.path().join("db.sqlite")).unwrap();
+        let mut app = App::new(utc(), Weekday::Mon, date("2025-08-10"), TagArgs::default());
+        app.refresh(&storage).unwrap();
+
+        type_keys(&mut app, &mut storage, "a09:00-10:00 Standup\n");
+        let event = app.selected_event().unwrap();
+        assert_eq!(event.title, "Standup");
+        assert_eq!(event.starts_at, "2025-08-10T09:00:00+00:00");
+
+        type_keys(&mut app, &mut storage, "g work, Team \n");
+        assert_eq!(app.selected_event().unwrap().tags, vec!["team", "work"]);
+
+        // The move prompt starts with the current time; replace it.
+        type_keys(&mut app, &mut storage, "v");
+        for _ in 0..5 {
+            app.handle_key(KeyEvent::from(KeyCode::Backspace), &mut storage)
+                .unwrap();
+        }
+        type_keys(&mut app, &mut storage, "+1d\n");
+        assert_eq!(app.selected, date("2025-08-11"));
+        assert_eq!(
+            app.selected_event().unwrap().starts_at,
+            "2025-08-11T09:00:00+00:00"
+        );
+
+        // Moving to another month reloads the grid around it.
+        type_keys(&mut app, &mut storage, "]");
+        assert_eq!(app.selected, date("2025-09-11"));
+        assert!(app.selected_event().is_none());
+        type_keys(&mut app, &mut storage, "[xn");
+        assert!(app.selected_event().is_some());
+        type_keys(&mut app, &mut storage, "xy");
+        assert!(app.selected_event().is_none());
+        assert!(storage.fetch_event_by_id(1).unwrap().is_none());
+    }
+}
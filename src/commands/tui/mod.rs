@@ -0,0 +1,54 @@
+mod app;
+mod view;
+
+use std::time::Duration;
+
+use anyhow::Result;
+use chrono::Utc;
+use ratatui::{
+    DefaultTerminal,
+    crossterm::event::{self, Event, KeyEventKind},
+};
+
+use crate::{cli::TuiCommand, storage::Storage};
+
+use super::{
+    calendar::parse_week_start,
+    events::{parse_date, parse_timezone},
+};
+
+use app::App;
+
+/// How often to check the database for writes from other processes.
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+pub fn run_tui(storage: &mut Storage, cmd: TuiCommand) -> Result<()> {
+    let zone = parse_timezone(cmd.tz.as_deref())?;
+    let week_start = parse_week_start(cmd.week_start.as_deref())?;
+    let selected = match cmd.date.as_deref() {
+        Some(date) => parse_date(date)?,
+        None => zone.date_of(Utc::now()),
+    };
+    let mut app = App::new(zone, week_start, selected, cmd.tags);
+    app.refresh(storage)?;
+
+    let mut terminal = ratatui::init();
+    let result = event_loop(&mut terminal, &mut app, storage);
+    ratatui::restore();
+    result
+}
+
+fn event_loop(terminal: &mut DefaultTerminal, app: &mut App, storage: &mut Storage) -> Result<()> {
+    while !app.quit {
+        terminal.draw(|frame| view::draw(frame, app))?;
+        if event::poll(POLL_INTERVAL)?
+            && let Event::Key(key) = event::read()?
+            && key.kind == KeyEventKind::Press
+            && let Err(err) = app.handle_key(key, storage)
+        {
+            app.status = Some(format!("{err:#}"));
+        }
+        app.poll_changes(storage)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,404 @@
+use chrono::{Datelike, Days, NaiveDate};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout, Position, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Paragraph, Wrap},
+};
+use unicode_width::UnicodeWidthStr;
+
+use crate::{
+    commands::events::{describe_reminder, format_event_timing, parse_utc},
+    storage::StoredEvent,
+};
+
+use super::app::{App, Entry, Mode, View};
+
+const HELP: &str = "←/→ day  J/K week  [/] period  ↑/↓ event  m/w/d view  t today  \
+                    a add  v move  e title  n note  g tags  x delete  q quit";
+
+pub(super) fn draw(frame: &mut Frame, app: &App) {
+    let [header, body, footer] = Layout::vertical([
+        Constraint::Length(1),
+        Constraint::Min(4),
+        Constraint::Length(2),
+    ])
+    .areas(frame.area());
+    let [calendar, detail] =
+        Layout::horizontal([Constraint::Percentage(70), Constraint::Percentage(30)]).areas(body);
+
+    frame.render_widget(
+        Paragraph::new(format!(" {}  ·  {}", period_title(app), app.zone.name()))
+            .style(Style::new().add_modifier(Modifier::BOLD)),
+        header,
+    );
+    match app.view {
+        View::Month => draw_month(frame, app, calendar),
+        View::Week => draw_week(frame, app, calendar),
+        View::Day => draw_day(frame, app, calendar),
+    }
+    draw_detail(frame, app, detail);
+    draw_footer(frame, app, footer);
+}
+
+fn period_title(app: &App) -> String {
+    match app.view {
+        View::Month => app.selected.format("%B %Y").to_string(),
+        View::Week => {
+            let start = app.week_start_date();
+            format!(
+                "Week of {} – {}",
+                start.format("%Y-%m-%d"),
+                (start + Days::new(6)).format("%m-%d")
+            )
+        }
+        View::Day => app.selected.format("%A %Y-%m-%d").to_string(),
+    }
+}
+
+fn draw_month(frame: &mut Frame, app: &App, area: Rect) {
+    let block = Block::bordered().title(" Month ");
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let start = app.grid_start();
+    let rows = (0..6)
+        .take_while(|week| {
+            let day = start + Days::new(week * 7);
+            *week == 0 || day.month() == app.selected.month()
+        })
+        .count();
+    let [names, grid] = Layout::vertical([Constraint::Length(1), Constraint::Min(0)]).areas(inner);
+    let columns = Layout::horizontal([Constraint::Ratio(1, 7); 7]);
+
+    for (column, cell) in columns.split(names).iter().enumerate() {
+        let day = start + Days::new(column as u64);
+        frame.render_widget(
+            Paragraph::new(day.format("%a").to_string())
+                .style(Style::new().add_modifier(Modifier::BOLD)),
+            *cell,
+        );
+    }
+    let weeks = Layout::vertical(vec![Constraint::Ratio(1, rows as u32); rows]).split(grid);
+    for (week, row) in weeks.iter().enumerate() {
+        for (column, cell) in columns.split(*row).iter().enumerate() {
+            let day = start + Days::new((week * 7 + column) as u64);
+            draw_month_cell(frame, app, day, *cell);
+        }
+    }
+}
+
+fn draw_month_cell(frame: &mut Frame, app: &App, day: NaiveDate, area: Rect) {
+    let mut number = Style::new();
+    if day.month() != app.selected.month() {
+        number = number.fg(Color::DarkGray);
+    }
+    if day == app.today() {
+        number = number.add_modifier(Modifier::BOLD | Modifier::UNDERLINED);
+    }
+    if day == app.selected {
+        number = number.add_modifier(Modifier::REVERSED);
+    }
+    let mut lines = vec![Line::from(Span::styled(
+        format!("{:>2}", day.day()),
+        number,
+    ))];
+
+    let entries: Vec<&Entry> = app.day_entries(day).collect();
+    let room = (area.height as usize).saturating_sub(1);
+    for (index, entry) in entries.iter().enumerate() {
+        if lines.len() == room && entries.len() > index + 1 {
+            lines.push(Line::styled(
+                format!("+{} more", entries.len() - index),
+                Style::new().fg(Color::DarkGray),
+            ));
+            break;
+        }
+        if lines.len() > room {
+            break;
+        }
+        lines.push(entry_line(app, day, index, entry, false));
+    }
+    // Leave a gap between columns.
+    let area = Rect {
+        width: area.width.saturating_sub(1),
+        ..area
+    };
+    frame.render_widget(Paragraph::new(lines), area);
+}
+
+fn draw_week(frame: &mut Frame, app: &App, area: Rect) {
+    let block = Block::bordered().title(" Week ");
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let start = app.week_start_date();
+    let columns = Layout::horizontal([Constraint::Ratio(1, 7); 7]).split(inner);
+    for (column, cell) in columns.iter().enumerate() {
+        let day = start + Days::new(column as u64);
+        let mut style = Style::new().add_modifier(Modifier::BOLD);
+        if day == app.today() {
+            style = style.add_modifier(Modifier::UNDERLINED);
+        }
+        if day == app.selected {
+            style = style.add_modifier(Modifier::REVERSED);
+        }
+        let mut lines = vec![
+            Line::styled(day.format("%a %m/%d").to_string(), style),
+            Line::default(),
+        ];
+        lines.extend(
+            app.day_entries(day)
+                .enumerate()
+                .map(|(index, entry)| entry_line(app, day, index, entry, false)),
+        );
+        let cell = Rect {
+            width: cell.width.saturating_sub(1),
+            ..*cell
+        };
+        frame.render_widget(Paragraph::new(lines), cell);
+    }
+}
+
+fn draw_day(frame: &mut Frame, app: &App, area: Rect) {
+    let block = Block::bordered().title(" Day ");
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let lines: Vec<Line> = app
+        .day_entries(app.selected)
+        .enumerate()
+        .map(|(index, entry)| entry_line(app, app.selected, index, entry, true))
+        .collect();
+    let lines = if lines.is_empty() {
+        vec![Line::styled(
+            "No events. Press a to add one.",
+            Style::new().fg(Color::DarkGray),
+        )]
+    } else {
+        lines
+    };
+    frame.render_widget(Paragraph::new(lines), inner);
+}
+
+/// One event as listed under `day`: its start time (or a bar for all-day
+/// and multi-day events) and title, with the time range when `long`.
+fn entry_line<'a>(
+    app: &App,
+    day: NaiveDate,
+    index: usize,
+    entry: &'a Entry,
+    long: bool,
+) -> Line<'a> {
+    let event = &entry.event;
+    let start = app
+        .zone
+        .format(parse_utc(&event.starts_at).unwrap_or_default(), "%H:%M");
+    let end = app
+        .zone
+        .format(parse_utc(&event.ends_at).unwrap_or_default(), "%H:%M");
+    let time = if event.all_day || entry.first != entry.last {
+        let marker = match (entry.first < day, entry.last > day) {
+            (false, false) => "■",
+            (false, true) => "┣",
+            (true, true) => "┃",
+            (true, false) => "┗",
+        };
+        match (long, event.all_day) {
+            (false, _) => format!("{marker} "),
+            (true, true) => format!("{marker} all day      "),
+            (true, false) => format!("{marker} {start}–{end}  "),
+        }
+    } else if long {
+        format!("{start}–{end}  ")
+    } else {
+        format!("{start} ")
+    };
+    let mut style = if event.all_day {
+        Style::new().fg(Color::Cyan)
+    } else {
+        Style::new()
+    };
+    if day == app.selected && index == app.cursor {
+        style = style.add_modifier(Modifier::REVERSED);
+    }
+    let mut spans = vec![
+        Span::styled(time, style.fg(Color::DarkGray)),
+        Span::styled(event.title.as_str(), style),
+    ];
+    if long && !event.tags.is_empty() {
+        spans.push(Span::styled(
+            format!("  [{}]", event.tags.join(", ")),
+            Style::new().fg(Color::DarkGray),
+        ));
+    }
+    Line::from(spans)
+}
+
+fn draw_detail(frame: &mut Frame, app: &App, area: Rect) {
+    let block = Block::bordered().title(" Event ");
+    let lines = match app.selected_event() {
+        Some(event) => detail_lines(app, event),
+        None => vec![Line::styled(
+            format!("No events on {}", app.selected),
+            Style::new().fg(Color::DarkGray),
+        )],
+    };
+    frame.render_widget(
+        Paragraph::new(lines)
+            .block(block)
+            .wrap(Wrap { trim: false }),
+        area,
+    );
+}
+
+fn detail_lines<'a>(app: &App, event: &'a StoredEvent) -> Vec<Line<'a>> {
+    let label = Style::new().fg(Color::DarkGray);
+    let mut lines = vec![
+        Line::styled(
+            format!("#{} {}", event.id, event.title),
+            Style::new().add_modifier(Modifier::BOLD),
+        ),
+        Line::raw(format_event_timing(event, &app.zone).unwrap_or_default()),
+    ];
+    for (name, value) in [
+        ("status: ", event.status.map(|status| status.as_str())),
+        ("location: ", event.location.as_deref()),
+        ("url: ", event.url.as_deref()),
+    ] {
+        if let Some(value) = value {
+            lines.push(Line::from(vec![
+                Span::styled(name, label),
+                Span::raw(value),
+            ]));
+        }
+    }
+    if let Some(rule) = &event.recurrence {
+        lines.push(Line::from(vec![
+            Span::styled("repeats: ", label),
+            Span::raw(rule.describe()),
+        ]));
+    }
+    if !event.tags.is_empty() {
+        lines.push(Line::from(vec![
+            Span::styled("tags: ", label),
+            Span::raw(event.tags.join(", ")),
+        ]));
+    }
+    if !event.alarms.is_empty() {
+        let reminders: Vec<_> = event.alarms.iter().map(|m| describe_reminder(*m)).collect();
+        lines.push(Line::from(vec![
+            Span::styled("reminders: ", label),
+            Span::raw(reminders.join(", ")),
+        ]));
+    }
+    if event.private {
+        lines.push(Line::styled("private: kept out of feeds", label));
+    }
+    if let Some(uid) = &event.uid {
+        lines.push(Line::from(vec![
+            Span::styled("uid: ", label),
+            Span::raw(uid.as_str()),
+        ]));
+    }
+    if !event.note.is_empty() {
+        lines.push(Line::default());
+        lines.extend(event.note.lines().map(Line::raw));
+    }
+    lines
+}
+
+fn draw_footer(frame: &mut Frame, app: &App, area: Rect) {
+    let [status, help] =
+        Layout::vertical([Constraint::Length(1), Constraint::Length(1)]).areas(area);
+    let dim = Style::new().fg(Color::DarkGray);
+    match &app.mode {
+        Mode::Normal => {
+            frame.render_widget(
+                Paragraph::new(app.status.clone().unwrap_or_default()),
+                status,
+            );
+            frame.render_widget(Paragraph::new(HELP).style(dim), help);
+        }
+        Mode::Prompt { action, input } => {
+            let prompt = format!("{}: ", action.label());
+            let cursor = (prompt.width() + input.width()) as u16;
+            let text = match &app.status {
+                Some(error) => format!("{}  ({error})", action.hint()),
+                None => action.hint().to_string(),
+            };
+            frame.render_widget(Paragraph::new(format!("{prompt}{input}")), status);
+            frame.render_widget(Paragraph::new(text).style(dim), help);
+            frame.set_cursor_position(Position::new(
+                status.x + cursor.min(status.width.saturating_sub(1)),
+                status.y,
+            ));
+        }
+        Mode::Confirm(event) => {
+            let choices = if event.recurrence.is_some() || event.parent_id.is_some() {
+                "y: this occurrence  s: every occurrence  n: keep"
+            } else {
+                "y: delete  n: keep"
+            };
+            frame.render_widget(
+                Paragraph::new(format!("Delete #{} {}?", event.id, event.title)),
+                status,
+            );
+            frame.render_widget(Paragraph::new(choices).style(dim), help);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Weekday;
+    use ratatui::{Terminal, backend::TestBackend};
+    use tempfile::tempdir;
+
+    use super::*;
+    use crate::{
+        cli::TagArgs,
+        commands::events::DisplayZone,
+        storage::{NewEvent, Storage},
+    };
+
+    #[test]
+    fn draws_each_view_with_the_selected_event() {
+        let dir = tempdir().unwrap();
+        let mut storage = Storage::new(&dir.path().join("db.sqlite")).unwrap();
+        storage
+            .insert_event(NewEvent {
+                title: "Planning".into(),
+                note: "agenda in the wiki".into(),
+                starts_at: "2025-08-12T09:00:00+00:00".into(),
+                ends_at: "2025-08-12T10:00:00+00:00".into(),
+                ..NewEvent::default()
+            })
+            .unwrap();
+        let date = NaiveDate::from_ymd_opt(2025, 8, 12).unwrap();
+        let mut app = App::new(
+            DisplayZone::Named(chrono_tz::UTC),
+            Weekday::Mon,
+            date,
+            TagArgs::default(),
+        );
+        app.refresh(&storage).unwrap();
+
+        let mut terminal = Terminal::new(TestBackend::new(120, 30)).unwrap();
+        for view in [View::Month, View::Week, View::Day] {
+            app.view = view;
+            terminal.draw(|frame| draw(frame, &app)).unwrap();
+            let screen: String = terminal
+                .backend()
+                .buffer()
+                .content()
+                .iter()
+                .map(|cell| cell.symbol())
+                .collect();
+            assert!(screen.contains("09:00"), "{view:?}:\n{screen}");
+            assert!(screen.contains("Planning"), "{view:?}:\n{screen}");
+            assert!(screen.contains("agenda in the wiki"), "{view:?}");
+        }
+    }
+}
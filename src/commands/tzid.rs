@@ -0,0 +1,544 @@
+//! `TZID` resolution for imported calendars: IANA names, the Windows zone
+//! names Outlook and Exchange write, and zones a calendar defines only
+//! through its own `VTIMEZONE` blocks.
+
+use std::collections::HashMap;
+
+use anyhow::{Context, Result, anyhow};
+use chrono::{DateTime, Duration, FixedOffset, LocalResult, NaiveDateTime, Offset, TimeZone, Utc};
+use chrono_tz::Tz;
+use ical::{parser::ical::component::IcalTimeZone, property::Property};
+
+use crate::recurrence::Recurrence;
+
+/// `VTIMEZONE` rules are followed up to the start of this year.
+const LAST_RULE_YEAR: i32 = 2100;
+
+/// Years from a series' start for which an IANA zone must keep the same
+/// offsets as a `VTIMEZONE`-only zone to stand in for it.
+const MATCH_YEARS: i64 = 10;
+
+/// Windows zone names and the IANA zone CLDR maps each to for the world
+/// ("001") territory, plus a few retired names older exports still use.
+const WINDOWS_ZONES: &[(&str, &str)] = &[
+    ("Dateline Standard Time", "Etc/GMT+12"),
+    ("UTC-11", "Etc/GMT+11"),
+    ("Aleutian Standard Time", "America/Adak"),
+    ("Hawaiian Standard Time", "Pacific/Honolulu"),
+    ("Marquesas Standard Time", "Pacific/Marquesas"),
+    ("Alaskan Standard Time", "America/Anchorage"),
+    ("UTC-09", "Etc/GMT+9"),
+    ("Pacific Standard Time (Mexico)", "America/Tijuana"),
+    ("UTC-08", "Etc/GMT+8"),
+    ("Pacific Standard Time", "America/Los_Angeles"),
+    ("US Mountain Standard Time", "America/Phoenix"),
+    ("Mountain Standard Time (Mexico)", "America/Mazatlan"),
+    ("Mountain Standard Time", "America/Denver"),
+    ("Yukon Standard Time", "America/Whitehorse"),
+    ("Central America Standard Time", "America/Guatemala"),
+    ("Central Standard Time", "America/Chicago"),
+    ("Easter Island Standard Time", "Pacific/Easter"),
+    ("Central Standard Time (Mexico)", "America/Mexico_City"),
+    ("Canada Central Standard Time", "America/Regina"),
+    ("SA Pacific Standard Time", "America/Bogota"),
+    ("Eastern Standard Time (Mexico)", "America/Cancun"),
+    ("Eastern Standard Time", "America/New_York"),
+    ("Haiti Standard Time", "America/Port-au-Prince"),
+    ("Cuba Standard Time", "America/Havana"),
+    ("US Eastern Standard Time", "America/Indiana/Indianapolis"),
+    ("Turks And Caicos Standard Time", "America/Grand_Turk"),
+    ("Paraguay Standard Time", "America/Asuncion"),
+    ("Atlantic Standard Time", "America/Halifax"),
+    ("Venezuela Standard Time", "America/Caracas"),
+    ("Central Brazilian Standard Time", "America/Cuiaba"),
+    ("SA Western Standard Time", "America/La_Paz"),
+    ("Pacific SA Standard Time", "America/Santiago"),
+    ("Newfoundland Standard Time", "America/St_Johns"),
+    ("Tocantins Standard Time", "America/Araguaina"),
+    ("E. South America Standard Time", "America/Sao_Paulo"),
+    ("SA Eastern Standard Time", "America/Cayenne"),
+    ("Argentina Standard Time", "America/Argentina/Buenos_Aires"),
+    ("Greenland Standard Time", "America/Nuuk"),
+    ("Montevideo Standard Time", "America/Montevideo"),
+    ("Magallanes Standard Time", "America/Punta_Arenas"),
+    ("Saint Pierre Standard Time", "America/Miquelon"),
+    ("Bahia Standard Time", "America/Bahia"),
+    ("UTC-02", "Etc/GMT+2"),
+    ("Mid-Atlantic Standard Time", "Etc/GMT+2"),
+    ("Azores Standard Time", "Atlantic/Azores"),
+    ("Cape Verde Standard Time", "Atlantic/Cape_Verde"),
+    ("UTC", "Etc/UTC"),
+    ("GMT Standard Time", "Europe/London"),
+    ("Greenwich Standard Time", "Atlantic/Reykjavik"),
+    ("Sao Tome Standard Time", "Africa/Sao_Tome"),
+    ("Morocco Standard Time", "Africa/Casablanca"),
+    ("W. Europe Standard Time", "Europe/Berlin"),
+    ("Central Europe Standard Time", "Europe/Budapest"),
+    ("Romance Standard Time", "Europe/Paris"),
+    ("Central European Standard Time", "Europe/Warsaw"),
+    ("W. Central Africa Standard Time", "Africa/Lagos"),
+    ("Jordan Standard Time", "Asia/Amman"),
+    ("GTB Standard Time", "Europe/Bucharest"),
+    ("Middle East Standard Time", "Asia/Beirut"),
+    ("Egypt Standard Time", "Africa/Cairo"),
+    ("E. Europe Standard Time", "Europe/Chisinau"),
+    ("Syria Standard Time", "Asia/Damascus"),
+    ("West Bank Standard Time", "Asia/Hebron"),
+    ("South Africa Standard Time", "Africa/Johannesburg"),
+    ("FLE Standard Time", "Europe/Kiev"),
+    ("Israel Standard Time", "Asia/Jerusalem"),
+    ("South Sudan Standard Time", "Africa/Juba"),
+    ("Kaliningrad Standard Time", "Europe/Kaliningrad"),
+    ("Sudan Standard Time", "Africa/Khartoum"),
+    ("Libya Standard Time", "Africa/Tripoli"),
+    ("Namibia Standard Time", "Africa/Windhoek"),
+    ("Arabic Standard Time", "Asia/Baghdad"),
+    ("Turkey Standard Time", "Europe/Istanbul"),
+    ("Arab Standard Time", "Asia/Riyadh"),
+    ("Belarus Standard Time", "Europe/Minsk"),
+    ("Russian Standard Time", "Europe/Moscow"),
+    ("E. Africa Standard Time", "Africa/Nairobi"),
+    ("Volgograd Standard Time", "Europe/Volgograd"),
+    ("Iran Standard Time", "Asia/Tehran"),
+    ("Arabian Standard Time", "Asia/Dubai"),
+    ("Astrakhan Standard Time", "Europe/Astrakhan"),
+    ("Azerbaijan Standard Time", "Asia/Baku"),
+    ("Russia Time Zone 3", "Europe/Samara"),
+    ("Mauritius Standard Time", "Indian/Mauritius"),
+    ("Saratov Standard Time", "Europe/Saratov"),
+    ("Georgian Standard Time", "Asia/Tbilisi"),
+    ("Caucasus Standard Time", "Asia/Yerevan"),
+    ("Armenian Standard Time", "Asia/Yerevan"),
+    ("Afghanistan Standard Time", "Asia/Kabul"),
+    ("West Asia Standard Time", "Asia/Tashkent"),
+    ("Ekaterinburg Standard Time", "Asia/Yekaterinburg"),
+    ("Pakistan Standard Time", "Asia/Karachi"),
+    ("Qyzylorda Standard Time", "Asia/Qyzylorda"),
+    ("India Standard Time", "Asia/Kolkata"),
+    ("Sri Lanka Standard Time", "Asia/Colombo"),
+    ("Nepal Standard Time", "Asia/Kathmandu"),
+    ("Central Asia Standard Time", "Asia/Almaty"),
+    ("Bangladesh Standard Time", "Asia/Dhaka"),
+    ("Omsk Standard Time", "Asia/Omsk"),
+    ("Myanmar Standard Time", "Asia/Yangon"),
+    ("SE Asia Standard Time", "Asia/Bangkok"),
+    ("Altai Standard Time", "Asia/Barnaul"),
+    ("W. Mongolia Standard Time", "Asia/Hovd"),
+    ("North Asia Standard Time", "Asia/Krasnoyarsk"),
+    ("N. Central Asia Standard Time", "Asia/Novosibirsk"),
+    ("Tomsk Standard Time", "Asia/Tomsk"),
+    ("China Standard Time", "Asia/Shanghai"),
+    ("North Asia East Standard Time", "Asia/Irkutsk"),
+    ("Singapore Standard Time", "Asia/Singapore"),
+    ("W. Australia Standard Time", "Australia/Perth"),
+    ("Taipei Standard Time", "Asia/Taipei"),
+    ("Ulaanbaatar Standard Time", "Asia/Ulaanbaatar"),
+    ("Aus Central W. Standard Time", "Australia/Eucla"),
+    ("Transbaikal Standard Time", "Asia/Chita"),
+    ("Tokyo Standard Time", "Asia/Tokyo"),
+    ("North Korea Standard Time", "Asia/Pyongyang"),
+    ("Korea Standard Time", "Asia/Seoul"),
+    ("Yakutsk Standard Time", "Asia/Yakutsk"),
+    ("Cen. Australia Standard Time", "Australia/Adelaide"),
+    ("AUS Central Standard Time", "Australia/Darwin"),
+    ("E. Australia Standard Time", "Australia/Brisbane"),
+    ("AUS Eastern Standard Time", "Australia/Sydney"),
+    ("West Pacific Standard Time", "Pacific/Port_Moresby"),
+    ("Tasmania Standard Time", "Australia/Hobart"),
+    ("Vladivostok Standard Time", "Asia/Vladivostok"),
+    ("Lord Howe Standard Time", "Australia/Lord_Howe"),
+    ("Bougainville Standard Time", "Pacific/Bougainville"),
+    ("Russia Time Zone 10", "Asia/Srednekolymsk"),
+    ("Magadan Standard Time", "Asia/Magadan"),
+    ("Norfolk Standard Time", "Pacific/Norfolk"),
+    ("Sakhalin Standard Time", "Asia/Sakhalin"),
+    ("Central Pacific Standard Time", "Pacific/Guadalcanal"),
+    ("Russia Time Zone 11", "Asia/Kamchatka"),
+    ("Kamchatka Standard Time", "Asia/Kamchatka"),
+    ("New Zealand Standard Time", "Pacific/Auckland"),
+    ("UTC+12", "Etc/GMT-12"),
+    ("Fiji Standard Time", "Pacific/Fiji"),
+    ("Chatham Islands Standard Time", "Pacific/Chatham"),
+    ("UTC+13", "Etc/GMT-13"),
+    ("Tonga Standard Time", "Pacific/Tongatapu"),
+    ("Samoa Standard Time", "Pacific/Apia"),
+    ("Line Islands Standard Time", "Pacific/Kiritimati"),
+    ("Mexico Standard Time", "America/Mexico_City"),
+    ("Mexico Standard Time 2", "America/Chihuahua"),
+];
+
+/// The IANA zone `tzid` names, either directly or as a Windows zone name.
+pub(super) fn iana_zone(tzid: &str) -> Option<Tz> {
+    let tzid = unquote(tzid);
+    if let Ok(zone) = tzid.parse() {
+        return Some(zone);
+    }
+    if let Some((_, iana)) = WINDOWS_ZONES
+        .iter()
+        .find(|(windows, _)| windows.eq_ignore_ascii_case(tzid))
+    {
+        return iana.parse().ok();
+    }
+    // Some producers put their own prefix before the IANA name, as in
+    // `/mozilla.org/20050126_1/Europe/Berlin`.
+    tzid.match_indices('/')
+        .find_map(|(at, _)| tzid[at + 1..].parse().ok())
+}
+
+fn unquote(tzid: &str) -> &str {
+    tzid.trim().trim_matches('"')
+}
+
+/// The zones one calendar can refer to: any IANA or Windows name, plus the
+/// zones its `VTIMEZONE` blocks define.
+#[derive(Default)]
+pub(super) struct TimeZones {
+    defined: HashMap<String, DefinedZone>,
+}
+
+impl TimeZones {
+    pub(super) fn new(definitions: &[IcalTimeZone]) -> Self {
+        let mut defined = HashMap::new();
+        for definition in definitions {
+            let Some(tzid) = property_value(&definition.properties, "TZID") else {
+                continue;
+            };
+            // A zone IANA already knows does not need its rules worked out.
+            if iana_zone(tzid).is_some() {
+                continue;
+            }
+            match DefinedZone::new(definition) {
+                Ok(zone) => {
+                    defined.insert(unquote(tzid).to_string(), zone);
+                }
+                Err(err) => eprintln!("Ignoring VTIMEZONE '{tzid}': {err:#}"),
+            }
+        }
+        Self { defined }
+    }
+
+    /// The instants local time `naive` in zone `tzid` can stand for.
+    pub(super) fn resolve_local(
+        &self,
+        tzid: &str,
+        naive: &NaiveDateTime,
+    ) -> Result<LocalResult<DateTime<Utc>>> {
+        if let Some(zone) = iana_zone(tzid) {
+            return Ok(zone
+                .from_local_datetime(naive)
+                .map(|local| local.with_timezone(&Utc)));
+        }
+        self.defined
+            .get(unquote(tzid))
+            .map(|zone| zone.resolve_local(naive))
+            .ok_or_else(|| anyhow!("unknown timezone '{tzid}'"))
+    }
+
+    /// The instant local time `naive` in zone `tzid` stands for (RFC 5545
+    /// section 3.3.5): the first of a time repeated when clocks fall back,
+    /// and a time skipped when they spring forward read with the offset in
+    /// force before the gap.
+    pub(super) fn instant(&self, tzid: &str, naive: &NaiveDateTime) -> Result<DateTime<Utc>> {
+        if let Some(instant) = self.resolve_local(tzid, naive)?.earliest() {
+            return Ok(instant);
+        }
+        // A day earlier is well clear of the gap; no zone moves its clocks
+        // twice within a day.
+        let before = (*naive - Duration::days(1)).and_utc();
+        let offset = match iana_zone(tzid) {
+            Some(zone) => zone.offset_from_utc_datetime(&before.naive_utc()).fix(),
+            None => self
+                .defined
+                .get(unquote(tzid))
+                .ok_or_else(|| anyhow!("unknown timezone '{tzid}'"))?
+                .offset_at(before),
+        };
+        Ok((*naive - offset_duration(offset)).and_utc())
+    }
+
+    /// An IANA zone using the same offsets as the `VTIMEZONE`-only zone
+    /// `tzid` for [`MATCH_YEARS`] from `start`, so a series in it can keep
+    /// following the calendar's DST changes once stored. Zones that Windows
+    /// names map to are tried first, since Outlook writes most such blocks.
+    pub(super) fn matching_iana_zone(&self, tzid: &str, start: DateTime<Utc>) -> Option<Tz> {
+        let zone = self.defined.get(unquote(tzid))?;
+        let end = start + Duration::days(365 * MATCH_YEARS);
+        // Quarterly samples catch DST one zone has and the other lacks;
+        // each side of every onset catches changes on different days.
+        let mut checks: Vec<_> = (0..MATCH_YEARS * 4)
+            .map(|quarter| start + Duration::days(91 * quarter))
+            .collect();
+        for (onset, _) in zone
+            .onsets
+            .iter()
+            .filter(|(onset, _)| (start..end).contains(onset))
+        {
+            checks.extend([*onset - Duration::seconds(1), *onset]);
+        }
+        let agrees = |candidate: &Tz| {
+            checks.iter().all(|instant| {
+                candidate
+                    .offset_from_utc_datetime(&instant.naive_utc())
+                    .fix()
+                    == zone.offset_at(*instant)
+            })
+        };
+        WINDOWS_ZONES
+            .iter()
+            .filter_map(|(_, iana)| iana.parse().ok())
+            .chain(chrono_tz::TZ_VARIANTS)
+            .find(agrees)
+    }
+}
+
+/// A zone known only from a `VTIMEZONE` block, flattened into the UTC
+/// instants at which its `STANDARD` and `DAYLIGHT` observances begin.
+struct DefinedZone {
+    /// Offset in force before the first onset.
+    initial: FixedOffset,
+    /// Onsets in order, each with the offset it brings in.
+    onsets: Vec<(DateTime<Utc>, FixedOffset)>,
+    /// Every offset the zone ever uses.
+    offsets: Vec<FixedOffset>,
+}
+
+impl DefinedZone {
+    fn new(definition: &IcalTimeZone) -> Result<Self> {
+        let horizon = Utc
+            .with_ymd_and_hms(LAST_RULE_YEAR, 1, 1, 0, 0, 0)
+            .single()
+            .ok_or_else(|| anyhow!("invalid rule horizon"))?;
+        let mut onsets = Vec::new();
+        let mut earliest: Option<(DateTime<Utc>, FixedOffset)> = None;
+        for observance in &definition.transitions {
+            let props = &observance.properties;
+            let offset = |name: &str| {
+                property_value(props, name)
+                    .ok_or_else(|| anyhow!("observance without {name}"))
+                    .and_then(parse_utc_offset)
+            };
+            let from = offset("TZOFFSETFROM")?;
+            let to = offset("TZOFFSETTO")?;
+            // Onsets are written in the local time that was in force before them.
+            let start = property_value(props, "DTSTART")
+                .ok_or_else(|| anyhow!("observance without DTSTART"))
+                .and_then(parse_local)?;
+            let start = from.from_utc_datetime(&(start - offset_duration(from)));
+
+            let mut found = Vec::new();
+            match property_value(props, "RRULE") {
+                Some(rule) => {
+                    let rule: Recurrence = rule.parse()?;
+                    rule.walk(&start, |onset| {
+                        if onset >= horizon {
+                            return false;
+                        }
+                        found.push(onset);
+                        true
+                    });
+                }
+                None => found.push(start.with_timezone(&Utc)),
+            }
+            for prop in props
+                .iter()
+                .filter(|prop| prop.name.eq_ignore_ascii_case("RDATE"))
+            {
+                for value in prop
+                    .value
+                    .as_deref()
+                    .unwrap_or_default()
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|value| !value.is_empty())
+                {
+                    found.push((parse_local(value)? - offset_duration(from)).and_utc());
+                }
+            }
+
+            if let Some(first) = found.iter().min().copied()
+                && earliest.is_none_or(|(onset, _)| first < onset)
+            {
+                earliest = Some((first, from));
+            }
+            onsets.extend(found.into_iter().map(|onset| (onset, to)));
+        }
+        let (_, initial) = earliest.ok_or_else(|| anyhow!("no STANDARD or DAYLIGHT rules"))?;
+        onsets.sort_by_key(|(onset, _)| *onset);
+        let mut offsets = vec![initial];
+        for (_, offset) in &onsets {
+            if !offsets.contains(offset) {
+                offsets.push(*offset);
+            }
+        }
+        Ok(Self {
+            initial,
+            onsets,
+            offsets,
+        })
+    }
+
+    fn offset_at(&self, instant: DateTime<Utc>) -> FixedOffset {
+        match self.onsets.partition_point(|(onset, _)| *onset <= instant) {
+            0 => self.initial,
+            after => self.onsets[after - 1].1,
+        }
+    }
+
+    /// Tries local time against every offset the zone uses and keeps the
+    /// instants where that offset is actually in force.
+    fn resolve_local(&self, naive: &NaiveDateTime) -> LocalResult<DateTime<Utc>> {
+        let mut found: Vec<DateTime<Utc>> = self
+            .offsets
+            .iter()
+            .map(|offset| (*offset, (*naive - offset_duration(*offset)).and_utc()))
+            .filter(|(offset, instant)| self.offset_at(*instant) == *offset)
+            .map(|(_, instant)| instant)
+            .collect();
+        found.sort();
+        found.dedup();
+        match found[..] {
+            [] => LocalResult::None,
+            [instant] => LocalResult::Single(instant),
+            [earliest, .., latest] => LocalResult::Ambiguous(earliest, latest),
+        }
+    }
+}
+
+fn offset_duration(offset: FixedOffset) -> Duration {
+    Duration::seconds(offset.local_minus_utc().into())
+}
+
+fn property_value<'a>(props: &'a [Property], name: &str) -> Option<&'a str> {
+    props
+        .iter()
+        .find(|prop| prop.name.eq_ignore_ascii_case(name))
+        .and_then(|prop| prop.value.as_deref())
+}
+
+fn parse_local(value: &str) -> Result<NaiveDateTime> {
+    NaiveDateTime::parse_from_str(value.trim(), "%Y%m%dT%H%M%S")
+        .with_context(|| format!("invalid local time '{value}'"))
+}
+
+/// A `TZOFFSETFROM`/`TZOFFSETTO` value such as `+0900` or `-034530`.
+fn parse_utc_offset(value: &str) -> Result<FixedOffset> {
+    let error = || anyhow!("invalid UTC offset '{value}'");
+    let value = value.trim();
+    let (sign, digits) = match value.as_bytes().first() {
+        Some(b'+') => (1, &value[1..]),
+        Some(b'-') => (-1, &value[1..]),
+        _ => return Err(error()),
+    };
+    if !matches!(digits.len(), 4 | 6) || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(error());
+    }
+    let part = |at: usize| digits.get(at..at + 2).map_or(Ok(0), str::parse::<i32>);
+    let seconds = part(0)? * 3600 + part(2)? * 60 + part(4)?;
+    FixedOffset::east_opt(sign * seconds).ok_or_else(error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ical::IcalParser;
+
+    #[test]
+    fn windows_names_map_to_known_zones() {
+        for (windows, iana) in WINDOWS_ZONES {
+            assert!(
+                iana.parse::<Tz>().is_ok(),
+                "{windows} maps to unknown {iana}"
+            );
+        }
+        assert_eq!(
+            iana_zone("Tokyo Standard Time"),
+            Some(chrono_tz::Asia::Tokyo)
+        );
+        assert_eq!(
+            iana_zone("\"w. europe standard time\""),
+            Some(chrono_tz::Europe::Berlin)
+        );
+        assert_eq!(
+            iana_zone("/mozilla.org/20050126_1/Europe/Berlin"),
+            Some(chrono_tz::Europe::Berlin)
+        );
+        assert_eq!(iana_zone("Somewhere Standard Time"), None);
+    }
+
+    #[test]
+    fn vtimezone_rules_give_local_offsets() {
+        let ics = "BEGIN:VCALENDAR\r\n\
+VERSION:2.0\r\n\
+BEGIN:VTIMEZONE\r\n\
+TZID:Eastern\r\n\
+BEGIN:STANDARD\r\n\
+DTSTART:16011104T020000\r\n\
+RRULE:FREQ=YEARLY;BYMONTH=11;BYDAY=1SU\r\n\
+TZOFFSETFROM:-0400\r\n\
+TZOFFSETTO:-0500\r\n\
+END:STANDARD\r\n\
+BEGIN:DAYLIGHT\r\n\
+DTSTART:16010311T020000\r\n\
+RRULE:FREQ=YEARLY;BYMONTH=3;BYDAY=2SU\r\n\
+TZOFFSETFROM:-0500\r\n\
+TZOFFSETTO:-0400\r\n\
+END:DAYLIGHT\r\n\
+END:VTIMEZONE\r\n\
+END:VCALENDAR\r\n";
+        let calendar = IcalParser::new(ics.as_bytes()).next().unwrap().unwrap();
+        let zones = TimeZones::new(&calendar.timezones);
+        let local = |value: &str| parse_local(value).unwrap();
+        let utc = |value: &str| {
+            DateTime::parse_from_rfc3339(value)
+                .unwrap()
+                .with_timezone(&Utc)
+        };
+
+        assert_eq!(
+            zones
+                .resolve_local("Eastern", &local("20250115T090000"))
+                .unwrap(),
+            LocalResult::Single(utc("2025-01-15T14:00:00Z"))
+        );
+        assert_eq!(
+            zones
+                .resolve_local("Eastern", &local("20250715T090000"))
+                .unwrap(),
+            LocalResult::Single(utc("2025-07-15T13:00:00Z"))
+        );
+        // Clocks skip from 02:00 to 03:00 on 9 March 2025.
+        assert_eq!(
+            zones
+                .resolve_local("Eastern", &local("20250309T023000"))
+                .unwrap(),
+            LocalResult::None
+        );
+        // Read with the offset in force before the gap.
+        assert_eq!(
+            zones.instant("Eastern", &local("20250309T023000")).unwrap(),
+            utc("2025-03-09T07:30:00Z")
+        );
+        assert_eq!(
+            zones
+                .instant("America/New_York", &local("20250309T023000"))
+                .unwrap(),
+            utc("2025-03-09T07:30:00Z")
+        );
+        // ...and repeat 01:00-02:00 on 2 November.
+        assert_eq!(
+            zones
+                .resolve_local("Eastern", &local("20251102T013000"))
+                .unwrap(),
+            LocalResult::Ambiguous(utc("2025-11-02T05:30:00Z"), utc("2025-11-02T06:30:00Z"))
+        );
+        assert!(
+            zones
+                .resolve_local("Central", &local("20250115T090000"))
+                .is_err()
+        );
+        assert_eq!(
+            zones.matching_iana_zone("Eastern", utc("2025-01-15T14:00:00Z")),
+            Some(chrono_tz::America::New_York)
+        );
+    }
+}
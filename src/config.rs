@@ -1,4 +1,4 @@
-use std::{fs, path::PathBuf};
+use std::{collections::BTreeMap, fs, path::PathBuf};
 
 use anyhow::{Context, Result};
 use directories::ProjectDirs;
@@ -14,6 +14,14 @@ pub struct Config {
     pub ical: IcalSection,
     #[serde(default)]
     pub import: ImportSection,
+    #[serde(default)]
+    pub templates: BTreeMap<String, TemplateSection>,
+    #[serde(default)]
+    pub calendar: CalendarSection,
+    #[serde(default)]
+    pub free: FreeSection,
+    #[serde(default)]
+    pub remind: RemindSection,
 }
 
 #[derive(Clone, Debug, Default, Deserialize)]
@@ -31,6 +39,33 @@ pub struct ImportSection {
     pub source: Option<PathBuf>,
 }
 
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct CalendarSection {
+    pub week_start: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct FreeSection {
+    pub hours: Option<String>,
+    #[serde(default)]
+    pub days: Vec<String>,
+    pub min_length: Option<String>,
+    pub buffer: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct RemindSection {
+    /// Reminder for events that have none of their own, e.g. "10m".
+    pub default_offset: Option<String>,
+    pub command: Option<String>,
+    pub interval: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct TemplateSection {
+    pub format: String,
+}
+
 #[derive(Clone, Debug, Default, Deserialize)]
 pub struct DatabaseSection {
     pub path: Option<PathBuf>,
@@ -82,6 +117,44 @@ impl Config {
     pub fn import_source_path(&self) -> Option<PathBuf> {
         self.import.source.clone()
     }
+
+    pub fn week_start(&self) -> Option<String> {
+        self.calendar.week_start.clone()
+    }
+
+    pub fn free_hours(&self) -> Option<String> {
+        self.free.hours.clone()
+    }
+
+    pub fn free_days(&self) -> Vec<String> {
+        self.free.days.clone()
+    }
+
+    pub fn free_min_length(&self) -> Option<String> {
+        self.free.min_length.clone()
+    }
+
+    pub fn free_buffer(&self) -> Option<String> {
+        self.free.buffer.clone()
+    }
+
+    pub fn remind_default_offset(&self) -> Option<String> {
+        self.remind.default_offset.clone()
+    }
+
+    pub fn remind_command(&self) -> Option<String> {
+        self.remind.command.clone()
+    }
+
+    pub fn remind_interval(&self) -> Option<String> {
+        self.remind.interval.clone()
+    }
+
+    pub fn template(&self, name: &str) -> Option<String> {
+        self.templates
+            .get(name)
+            .map(|section| section.format.clone())
+    }
 }
 
 #[cfg(test)]
@@ -140,6 +213,21 @@ mod tests {
 
             [import]
             source = "/tmp/import.ics"
+
+            [templates.compact]
+            format = "{start:%H:%M} {title}"
+
+            [calendar]
+            week_start = "sunday"
+
+            [free]
+            hours = "10:00-17:00"
+            days = ["mon", "thu"]
+            buffer = "15m"
+
+            [remind]
+            default_offset = "10m"
+            command = 'notify-send "{title}" "{start}"'
             "#,
         )
         .unwrap();
@@ -161,6 +249,22 @@ mod tests {
             cfg.import_source_path().as_deref(),
             Some(std::path::Path::new("/tmp/import.ics"))
         );
+        assert_eq!(
+            cfg.template("compact").as_deref(),
+            Some("{start:%H:%M} {title}")
+        );
+        assert!(cfg.template("missing").is_none());
+        assert_eq!(cfg.week_start().as_deref(), Some("sunday"));
+        assert_eq!(cfg.free_hours().as_deref(), Some("10:00-17:00"));
+        assert_eq!(cfg.free_days(), vec!["mon", "thu"]);
+        assert!(cfg.free_min_length().is_none());
+        assert_eq!(cfg.free_buffer().as_deref(), Some("15m"));
+        assert_eq!(cfg.remind_default_offset().as_deref(), Some("10m"));
+        assert_eq!(
+            cfg.remind_command().as_deref(),
+            Some(r#"notify-send "{title}" "{start}""#)
+        );
+        assert!(cfg.remind_interval().is_none());
     }
 
     #[test]
@@ -173,6 +277,7 @@ mod tests {
         assert!(cfg.rss_output_path().is_none());
         assert!(cfg.ical_output_path().is_none());
         assert!(cfg.import_source_path().is_none());
+        assert!(cfg.week_start().is_none());
     }
 
     #[test]
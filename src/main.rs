@@ -1,13 +1,16 @@
 mod cli;
 mod commands;
 mod config;
+mod recurrence;
 mod storage;
 
 use anyhow::Result;
 use clap::Parser;
 use cli::{Cli, Command};
 use commands::{
-    add_event, delete_event, generate_ical, generate_rss, import_ics, list_events, move_event,
+    add_event, delete_event, edit_event, find_free_slots, generate_freebusy, generate_ical,
+    generate_rss, import_ics, list_conflicts, list_events, move_event, run_reminders, run_tui,
+    search_events, show_month, show_week,
 };
 use config::{load_config, resolve_database_path};
 use storage::Storage;
@@ -20,9 +23,19 @@ fn main() -> Result<()> {
 
     match cli.command {
         Command::Add(cmd) => add_event(&mut storage, cmd),
-        Command::List(cmd) => list_events(&storage, cmd),
+        Command::List(mut cmd) => {
+            if let Some(format) = cmd
+                .template
+                .as_deref()
+                .and_then(|name| config.template(name))
+            {
+                cmd.template = Some(format);
+            }
+            list_events(&storage, cmd)
+        }
         Command::Delete(cmd) => delete_event(&mut storage, cmd),
         Command::Move(cmd) => move_event(&mut storage, cmd),
+        Command::Edit(cmd) => edit_event(&mut storage, cmd),
         Command::Rss(mut cmd) => {
             if cmd.output.is_none() {
                 cmd.output = config.rss_output_path();
@@ -35,11 +48,59 @@ fn main() -> Result<()> {
             }
             generate_ical(&storage, cmd)
         }
+        Command::Freebusy(cmd) => generate_freebusy(&storage, cmd),
         Command::Import(mut cmd) => {
             if cmd.path.is_none() {
                 cmd.path = config.import_source_path();
             }
             import_ics(&mut storage, cmd)
         }
+        Command::Search(cmd) => search_events(&storage, cmd),
+        Command::Cal(mut cmd) => {
+            if cmd.layout.week_start.is_none() {
+                cmd.layout.week_start = config.week_start();
+            }
+            show_month(&storage, cmd)
+        }
+        Command::Week(mut cmd) => {
+            if cmd.layout.week_start.is_none() {
+                cmd.layout.week_start = config.week_start();
+            }
+            show_week(&storage, cmd)
+        }
+        Command::Tui(mut cmd) => {
+            if cmd.week_start.is_none() {
+                cmd.week_start = config.week_start();
+            }
+            run_tui(&mut storage, cmd)
+        }
+        Command::Conflicts(cmd) => list_conflicts(&storage, cmd),
+        Command::Free(mut cmd) => {
+            if cmd.hours.is_none() {
+                cmd.hours = config.free_hours();
+            }
+            if cmd.days.is_empty() {
+                cmd.days = config.free_days();
+            }
+            if cmd.min.is_none() {
+                cmd.min = config.free_min_length();
+            }
+            if cmd.buffer.is_none() {
+                cmd.buffer = config.free_buffer();
+            }
+            find_free_slots(&storage, cmd)
+        }
+        Command::Remind(mut cmd) => {
+            if cmd.command.is_none() {
+                cmd.command = config.remind_command();
+            }
+            if cmd.default_offset.is_none() {
+                cmd.default_offset = config.remind_default_offset();
+            }
+            if cmd.interval.is_none() {
+                cmd.interval = config.remind_interval();
+            }
+            run_reminders(&mut storage, cmd)
+        }
     }
 }
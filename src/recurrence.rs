@@ -0,0 +1,558 @@
+use std::{fmt, str::FromStr};
+
+use anyhow::{Context, Result, anyhow};
+use chrono::{
+    DateTime, Datelike, Days, Duration, Month, Months, NaiveDate, NaiveDateTime, TimeZone, Utc,
+    Weekday,
+};
+
+/// Upper bound on generated periods so malformed rules cannot spin forever.
+const MAX_PERIODS: u32 = 100_000;
+/// Upper bound on occurrences returned for a single window.
+const MAX_OCCURRENCES: usize = 10_000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+/// One `BYDAY` entry such as `MO`, `2TU` or `-1FR`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ByDay {
+    pub ordinal: Option<i32>,
+    pub weekday: Weekday,
+}
+
+/// The subset of RFC 5545 `RRULE` that toki-note understands.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Recurrence {
+    pub frequency: Frequency,
+    pub interval: u32,
+    pub by_day: Vec<ByDay>,
+    /// Months (1-12) the rule is limited to; yearly `BYDAY` ordinals then
+    /// count within each month, as in "last Sunday of October".
+    pub by_month: Vec<u32>,
+    pub count: Option<u32>,
+    pub until: Option<DateTime<Utc>>,
+}
+
+impl Recurrence {
+    pub fn new(frequency: Frequency) -> Self {
+        Self {
+            frequency,
+            interval: 1,
+            by_day: Vec::new(),
+            by_month: Vec::new(),
+            count: None,
+            until: None,
+        }
+    }
+
+    /// Start instants of every occurrence of a series beginning at `start` whose
+    /// `[start, start + duration)` span overlaps `[window_start, window_end)`.
+    ///
+    /// `start` carries the zone the rule is evaluated in, so "weekly on Monday
+    /// at 09:00" stays on Monday at 09:00 local time across DST changes.
+    pub fn occurrences<Z: TimeZone>(
+        &self,
+        start: &DateTime<Z>,
+        duration: Duration,
+        window_start: DateTime<Utc>,
+        window_end: DateTime<Utc>,
+    ) -> Vec<DateTime<Utc>> {
+        let mut found = Vec::new();
+        self.walk(start, |instant| {
+            if instant >= window_end {
+                return false;
+            }
+            if instant + duration > window_start {
+                found.push(instant);
+            }
+            found.len() < MAX_OCCURRENCES
+        });
+        found
+    }
+
+    /// Calls `visit` with each occurrence start in order until it returns
+    /// `false` or the rule is exhausted by `COUNT`/`UNTIL`.
+    pub fn walk<Z: TimeZone>(
+        &self,
+        start: &DateTime<Z>,
+        mut visit: impl FnMut(DateTime<Utc>) -> bool,
+    ) {
+        let zone = start.timezone();
+        let first_date = start.date_naive();
+        let time = start.time();
+        let mut produced = 0u32;
+
+        // DTSTART always counts as the first instance, even when it does not
+        // match BYDAY (RFC 5545 section 3.8.5.3).
+        let mut emit = |instant: DateTime<Utc>| -> bool {
+            if self.count.is_some_and(|count| produced >= count) {
+                return false;
+            }
+            if self.until.is_some_and(|until| instant > until) {
+                return false;
+            }
+            produced += 1;
+            visit(instant)
+        };
+        if !emit(start.with_timezone(&Utc)) {
+            return;
+        }
+
+        for period in 0..MAX_PERIODS {
+            let Some(dates) = self.period_dates(first_date, period) else {
+                return;
+            };
+            for date in dates.into_iter().filter(|date| *date > first_date) {
+                // Local times skipped by a DST gap simply produce no instance.
+                let Some(local) = zone.from_local_datetime(&date.and_time(time)).earliest() else {
+                    continue;
+                };
+                if !emit(local.with_timezone(&Utc)) {
+                    return;
+                }
+            }
+        }
+    }
+
+    fn period_dates(&self, first: NaiveDate, period: u32) -> Option<Vec<NaiveDate>> {
+        let step = period.checked_mul(self.interval.max(1))?;
+        let mut dates = match self.frequency {
+            Frequency::Daily => {
+                let date = first.checked_add_days(Days::new(step as u64))?;
+                if self.by_day.is_empty() || self.by_day.iter().any(|d| d.weekday == date.weekday())
+                {
+                    vec![date]
+                } else {
+                    Vec::new()
+                }
+            }
+            Frequency::Weekly => {
+                let week_start = first
+                    .checked_sub_days(Days::new(first.weekday().num_days_from_monday() as u64))?
+                    .checked_add_days(Days::new(step as u64 * 7))?;
+                let weekdays: Vec<Weekday> = if self.by_day.is_empty() {
+                    vec![first.weekday()]
+                } else {
+                    self.by_day.iter().map(|d| d.weekday).collect()
+                };
+                weekdays
+                    .into_iter()
+                    .filter_map(|weekday| {
+                        week_start
+                            .checked_add_days(Days::new(weekday.num_days_from_monday() as u64))
+                    })
+                    .collect()
+            }
+            Frequency::Monthly => {
+                let month_start = first.with_day(1)?.checked_add_months(Months::new(step))?;
+                if self.by_day.is_empty() {
+                    month_start.with_day(first.day()).into_iter().collect()
+                } else {
+                    let next = month_start.checked_add_months(Months::new(1))?;
+                    self.by_day_dates(month_start, next)
+                }
+            }
+            Frequency::Yearly if !self.by_month.is_empty() => {
+                let year = first.year().checked_add(step as i32)?;
+                let mut dates = Vec::new();
+                for &month in &self.by_month {
+                    let month_start = NaiveDate::from_ymd_opt(year, month, 1)?;
+                    if self.by_day.is_empty() {
+                        dates.extend(month_start.with_day(first.day()));
+                    } else {
+                        let next = month_start.checked_add_months(Months::new(1))?;
+                        dates.extend(self.by_day_dates(month_start, next));
+                    }
+                }
+                dates
+            }
+            Frequency::Yearly => {
+                let year = first.year().checked_add(step as i32)?;
+                if self.by_day.is_empty() {
+                    NaiveDate::from_ymd_opt(year, first.month(), first.day())
+                        .into_iter()
+                        .collect()
+                } else {
+                    let year_start = NaiveDate::from_ymd_opt(year, 1, 1)?;
+                    let next = NaiveDate::from_ymd_opt(year + 1, 1, 1)?;
+                    self.by_day_dates(year_start, next)
+                }
+            }
+        };
+        if !self.by_month.is_empty() {
+            dates.retain(|date| self.by_month.contains(&date.month()));
+        }
+        dates.sort();
+        dates.dedup();
+        Some(dates)
+    }
+
+    fn by_day_dates(&self, from: NaiveDate, until: NaiveDate) -> Vec<NaiveDate> {
+        let mut dates = Vec::new();
+        for rule in &self.by_day {
+            let matching: Vec<NaiveDate> = from
+                .iter_days()
+                .take_while(|date| *date < until)
+                .filter(|date| date.weekday() == rule.weekday)
+                .collect();
+            match rule.ordinal {
+                None => dates.extend(matching),
+                Some(n) if n > 0 => dates.extend(matching.get(n as usize - 1)),
+                Some(n) => dates.extend(
+                    matching
+                        .len()
+                        .checked_sub(n.unsigned_abs() as usize)
+                        .and_then(|idx| matching.get(idx)),
+                ),
+            }
+        }
+        dates
+    }
+
+    /// Short human description used by `list`, e.g. `every 2 weeks on MO,WE, 5 times`.
+    pub fn describe(&self) -> String {
+        let unit = match self.frequency {
+            Frequency::Daily => "day",
+            Frequency::Weekly => "week",
+            Frequency::Monthly => "month",
+            Frequency::Yearly => "year",
+        };
+        let mut text = if self.interval <= 1 {
+            match self.frequency {
+                Frequency::Daily => "daily".to_string(),
+                Frequency::Weekly => "weekly".to_string(),
+                Frequency::Monthly => "monthly".to_string(),
+                Frequency::Yearly => "yearly".to_string(),
+            }
+        } else {
+            format!("every {} {unit}s", self.interval)
+        };
+        if !self.by_day.is_empty() {
+            text.push_str(&format!(" on {}", format_by_day(&self.by_day)));
+        }
+        if !self.by_month.is_empty() {
+            let months: Vec<&str> = self
+                .by_month
+                .iter()
+                .filter_map(|month| Month::try_from(*month as u8).ok())
+                .map(|month| month.name())
+                .collect();
+            text.push_str(&format!(" in {}", months.join(", ")));
+        }
+        if let Some(count) = self.count {
+            text.push_str(&format!(", {count} times"));
+        }
+        if let Some(until) = self.until {
+            text.push_str(&format!(", until {}", until.format("%Y-%m-%d %H:%M UTC")));
+        }
+        text
+    }
+}
+
+impl fmt::Display for Recurrence {
+    /// Formats the rule as an RRULE value (without the `RRULE:` prefix).
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let freq = match self.frequency {
+            Frequency::Daily => "DAILY",
+            Frequency::Weekly => "WEEKLY",
+            Frequency::Monthly => "MONTHLY",
+            Frequency::Yearly => "YEARLY",
+        };
+        write!(f, "FREQ={freq}")?;
+        if self.interval > 1 {
+            write!(f, ";INTERVAL={}", self.interval)?;
+        }
+        if !self.by_month.is_empty() {
+            let months: Vec<String> = self.by_month.iter().map(u32::to_string).collect();
+            write!(f, ";BYMONTH={}", months.join(","))?;
+        }
+        if !self.by_day.is_empty() {
+            write!(f, ";BYDAY={}", format_by_day(&self.by_day))?;
+        }
+        if let Some(count) = self.count {
+            write!(f, ";COUNT={count}")?;
+        }
+        if let Some(until) = self.until {
+            write!(f, ";UNTIL={}", until.format("%Y%m%dT%H%M%SZ"))?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Recurrence {
+    type Err = anyhow::Error;
+
+    fn from_str(input: &str) -> Result<Self> {
+        let value = input.trim();
+        let value = value.strip_prefix("RRULE:").unwrap_or(value);
+        let mut frequency = None;
+        let mut rule = Recurrence::new(Frequency::Daily);
+
+        for part in value.split(';').filter(|part| !part.is_empty()) {
+            let (key, val) = part
+                .split_once('=')
+                .ok_or_else(|| anyhow!("malformed RRULE part '{part}'"))?;
+            match key.to_ascii_uppercase().as_str() {
+                "FREQ" => frequency = Some(parse_frequency(val)?),
+                "INTERVAL" => {
+                    rule.interval = val
+                        .parse()
+                        .ok()
+                        .filter(|n| *n > 0)
+                        .ok_or_else(|| anyhow!("invalid INTERVAL '{val}'"))?;
+                }
+                "BYDAY" => {
+                    rule.by_day = val
+                        .split(',')
+                        .map(parse_by_day)
+                        .collect::<Result<Vec<_>>>()?;
+                }
+                "BYMONTH" => {
+                    rule.by_month = val
+                        .split(',')
+                        .map(|month| {
+                            month
+                                .trim()
+                                .parse()
+                                .ok()
+                                .filter(|month| (1..=12).contains(month))
+                                .ok_or_else(|| anyhow!("invalid BYMONTH value '{month}'"))
+                        })
+                        .collect::<Result<Vec<_>>>()?;
+                }
+                "COUNT" => {
+                    rule.count = Some(
+                        val.parse()
+                            .with_context(|| format!("invalid COUNT '{val}'"))?,
+                    );
+                }
+                "UNTIL" => rule.until = Some(parse_until(val)?),
+                // Week start only affects rules we do not support (BYWEEKNO etc.).
+                "WKST" => {}
+                other => return Err(anyhow!("unsupported RRULE part '{other}'")),
+            }
+        }
+
+        rule.frequency = frequency.ok_or_else(|| anyhow!("RRULE is missing FREQ"))?;
+        Ok(rule)
+    }
+}
+
+pub fn parse_frequency(value: &str) -> Result<Frequency> {
+    match value.trim().to_ascii_uppercase().as_str() {
+        "DAILY" => Ok(Frequency::Daily),
+        "WEEKLY" => Ok(Frequency::Weekly),
+        "MONTHLY" => Ok(Frequency::Monthly),
+        "YEARLY" => Ok(Frequency::Yearly),
+        other => Err(anyhow!("unsupported FREQ '{other}'")),
+    }
+}
+
+pub fn parse_by_day(value: &str) -> Result<ByDay> {
+    let trimmed = value.trim();
+    if trimmed.len() < 2 || !trimmed.is_char_boundary(trimmed.len() - 2) {
+        return Err(anyhow!("invalid BYDAY value '{value}'"));
+    }
+    let (ordinal, code) = trimmed.split_at(trimmed.len() - 2);
+    let weekday = match code.to_ascii_uppercase().as_str() {
+        "MO" => Weekday::Mon,
+        "TU" => Weekday::Tue,
+        "WE" => Weekday::Wed,
+        "TH" => Weekday::Thu,
+        "FR" => Weekday::Fri,
+        "SA" => Weekday::Sat,
+        "SU" => Weekday::Sun,
+        _ => return Err(anyhow!("invalid BYDAY weekday '{value}'")),
+    };
+    let ordinal = if ordinal.is_empty() {
+        None
+    } else {
+        let n: i32 = ordinal
+            .trim_start_matches('+')
+            .parse()
+            .with_context(|| format!("invalid BYDAY ordinal '{value}'"))?;
+        if n == 0 {
+            return Err(anyhow!("invalid BYDAY ordinal '{value}'"));
+        }
+        Some(n)
+    };
+    Ok(ByDay { ordinal, weekday })
+}
+
+fn parse_until(value: &str) -> Result<DateTime<Utc>> {
+    let trimmed = value.trim();
+    if let Some(stripped) = trimmed.strip_suffix('Z') {
+        let naive = NaiveDateTime::parse_from_str(stripped, "%Y%m%dT%H%M%S")
+            .with_context(|| format!("invalid UNTIL '{value}'"))?;
+        return Ok(Utc.from_utc_datetime(&naive));
+    }
+    if let Ok(naive) = NaiveDateTime::parse_from_str(trimmed, "%Y%m%dT%H%M%S") {
+        return Ok(Utc.from_utc_datetime(&naive));
+    }
+    // A DATE value is inclusive of the whole day.
+    let date = NaiveDate::parse_from_str(trimmed, "%Y%m%d")
+        .with_context(|| format!("invalid UNTIL '{value}'"))?;
+    date.and_hms_opt(23, 59, 59)
+        .map(|dt| dt.and_utc())
+        .ok_or_else(|| anyhow!("invalid UNTIL '{value}'"))
+}
+
+fn format_by_day(days: &[ByDay]) -> String {
+    days.iter()
+        .map(|day| {
+            let code = match day.weekday {
+                Weekday::Mon => "MO",
+                Weekday::Tue => "TU",
+                Weekday::Wed => "WE",
+                Weekday::Thu => "TH",
+                Weekday::Fri => "FR",
+                Weekday::Sat => "SA",
+                Weekday::Sun => "SU",
+            };
+            match day.ordinal {
+                Some(n) => format!("{n}{code}"),
+                None => code.to_string(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono_tz::Asia::Tokyo;
+
+    fn utc(value: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(value)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    #[test]
+    fn rrule_round_trips_through_display() {
+        let rule: Recurrence = "FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,-1FR;COUNT=5"
+            .parse()
+            .unwrap();
+        assert_eq!(rule.frequency, Frequency::Weekly);
+        assert_eq!(rule.interval, 2);
+        assert_eq!(rule.by_day[1].ordinal, Some(-1));
+        assert_eq!(
+            rule.to_string(),
+            "FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,-1FR;COUNT=5"
+        );
+
+        let until: Recurrence = "FREQ=DAILY;UNTIL=20250110T000000Z".parse().unwrap();
+        assert_eq!(until.until, Some(utc("2025-01-10T00:00:00+00:00")));
+        assert!("FREQ=HOURLY".parse::<Recurrence>().is_err());
+        assert!("FREQ=DAILY;BYMINUTE=5".parse::<Recurrence>().is_err());
+    }
+
+    #[test]
+    fn weekly_by_day_expands_in_event_zone() {
+        // Monday 08:00 in Tokyo is Sunday 23:00 UTC; BYDAY must follow the local day.
+        let rule: Recurrence = "FREQ=WEEKLY;BYDAY=MO,WE".parse().unwrap();
+        let start = Tokyo.with_ymd_and_hms(2025, 8, 4, 8, 0, 0).unwrap();
+        let found = rule.occurrences(
+            &start,
+            Duration::minutes(30),
+            utc("2025-08-01T00:00:00+00:00"),
+            utc("2025-08-11T00:00:00+00:00"),
+        );
+        assert_eq!(
+            found,
+            vec![
+                utc("2025-08-03T23:00:00+00:00"),
+                utc("2025-08-05T23:00:00+00:00"),
+                utc("2025-08-10T23:00:00+00:00"),
+            ]
+        );
+    }
+
+    #[test]
+    fn count_and_until_limit_the_series() {
+        let start = utc("2025-01-01T09:00:00+00:00");
+        let far = utc("2030-01-01T00:00:00+00:00");
+
+        let mut rule = Recurrence::new(Frequency::Daily);
+        rule.count = Some(3);
+        let found = rule.occurrences(&start, Duration::hours(1), start, far);
+        assert_eq!(found.len(), 3);
+
+        let mut rule = Recurrence::new(Frequency::Daily);
+        rule.interval = 2;
+        rule.until = Some(utc("2025-01-05T09:00:00+00:00"));
+        let found = rule.occurrences(&start, Duration::hours(1), start, far);
+        assert_eq!(found.last(), Some(&utc("2025-01-05T09:00:00+00:00")));
+        assert_eq!(found.len(), 3);
+    }
+
+    #[test]
+    fn monthly_ordinal_weekdays_and_short_months() {
+        let rule: Recurrence = "FREQ=MONTHLY;BYDAY=-1FR;COUNT=3".parse().unwrap();
+        let start = utc("2025-01-31T12:00:00+00:00");
+        let found = rule.occurrences(
+            &start,
+            Duration::hours(1),
+            start,
+            utc("2026-01-01T00:00:00+00:00"),
+        );
+        assert_eq!(
+            found,
+            vec![
+                utc("2025-01-31T12:00:00+00:00"),
+                utc("2025-02-28T12:00:00+00:00"),
+                utc("2025-03-28T12:00:00+00:00"),
+            ]
+        );
+
+        // Months without a 31st are skipped rather than clamped.
+        let rule = Recurrence::new(Frequency::Monthly);
+        let found = rule.occurrences(
+            &start,
+            Duration::hours(1),
+            start,
+            utc("2025-06-01T00:00:00+00:00"),
+        );
+        assert_eq!(found.len(), 3);
+    }
+
+    #[test]
+    fn yearly_by_month_picks_weekdays_within_the_month() {
+        // The EU switch to summer time: last Sunday of March at 01:00 UTC.
+        let rule: Recurrence = "FREQ=YEARLY;BYMONTH=3;BYDAY=-1SU".parse().unwrap();
+        assert_eq!(rule.to_string(), "FREQ=YEARLY;BYMONTH=3;BYDAY=-1SU");
+        assert_eq!(rule.describe(), "yearly on -1SU in March");
+        let start = utc("1996-03-31T01:00:00+00:00");
+        let found = rule.occurrences(
+            &start,
+            Duration::zero(),
+            utc("2024-01-01T00:00:00+00:00"),
+            utc("2026-01-01T00:00:00+00:00"),
+        );
+        assert_eq!(
+            found,
+            vec![
+                utc("2024-03-31T01:00:00+00:00"),
+                utc("2025-03-30T01:00:00+00:00"),
+            ]
+        );
+
+        let rule: Recurrence = "FREQ=MONTHLY;BYMONTH=1,7".parse().unwrap();
+        let start = utc("2025-01-15T09:00:00+00:00");
+        let found = rule.occurrences(
+            &start,
+            Duration::hours(1),
+            start,
+            utc("2026-03-01T00:00:00+00:00"),
+        );
+        assert_eq!(found.len(), 3);
+        assert!("FREQ=YEARLY;BYMONTH=13".parse::<Recurrence>().is_err());
+    }
+}
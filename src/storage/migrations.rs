@@ -0,0 +1,305 @@
+use anyhow::{Context, Result, anyhow};
+use rusqlite::{Connection, OptionalExtension, Transaction, params};
+
+/// A single schema step. Versions are stored in `PRAGMA user_version` and must
+/// be strictly increasing; never edit a migration once it has shipped.
+struct Migration {
+    version: i64,
+    description: &'static str,
+    apply: fn(&Transaction<'_>) -> rusqlite::Result<()>,
+}
+
+const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "create events and event_tags",
+        apply: create_base_schema,
+    },
+    Migration {
+        version: 2,
+        description: "add recurrence rule and zone to events",
+        apply: add_recurrence_columns,
+    },
+    Migration {
+        version: 3,
+        description: "add recurrence exceptions and occurrence overrides",
+        apply: add_recurrence_exceptions,
+    },
+    Migration {
+        version: 4,
+        description: "add full-text index over titles and notes",
+        apply: add_full_text_index,
+    },
+    Migration {
+        version: 5,
+        description: "add private flag to events",
+        apply: add_private_flag,
+    },
+    Migration {
+        version: 6,
+        description: "add location, url and status to events",
+        apply: add_meeting_details,
+    },
+    Migration {
+        version: 7,
+        description: "add event_alarms",
+        apply: add_event_alarms,
+    },
+    Migration {
+        version: 8,
+        description: "add fired_alarms",
+        apply: add_fired_alarms,
+    },
+    Migration {
+        version: 9,
+        description: "add import revisions",
+        apply: add_import_revisions,
+    },
+];
+
+pub(super) fn latest_version() -> i64 {
+    MIGRATIONS.last().map(|m| m.version).unwrap_or(0)
+}
+
+pub(super) fn current_version(conn: &Connection) -> Result<i64> {
+    Ok(conn.pragma_query_value(None, "user_version", |row| row.get(0))?)
+}
+
+pub(super) fn run(conn: &mut Connection) -> Result<()> {
+    let current = current_version(conn)?;
+    let latest = latest_version();
+    if current > latest {
+        return Err(anyhow!(
+            "database schema version {current} is newer than this toki-note supports ({latest}); \
+             upgrade toki-note to open it"
+        ));
+    }
+
+    for migration in MIGRATIONS.iter().filter(|m| m.version > current) {
+        let tx = conn.transaction()?;
+        (migration.apply)(&tx).with_context(|| {
+            format!(
+                "failed to apply migration {} ({})",
+                migration.version, migration.description
+            )
+        })?;
+        tx.pragma_update(None, "user_version", migration.version)?;
+        tx.commit()?;
+    }
+    Ok(())
+}
+
+fn create_base_schema(tx: &Transaction<'_>) -> rusqlite::Result<()> {
+    tx.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS events (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            title TEXT NOT NULL,
+            starts_at TEXT NOT NULL,
+            ends_at TEXT NOT NULL,
+            note TEXT NOT NULL DEFAULT '',
+            all_day INTEGER NOT NULL DEFAULT 0,
+            uid TEXT
+        );
+        CREATE TABLE IF NOT EXISTS event_tags (
+            event_id INTEGER NOT NULL,
+            tag TEXT NOT NULL,
+            UNIQUE (event_id, tag),
+            FOREIGN KEY (event_id) REFERENCES events(id) ON DELETE CASCADE
+        );
+        "#,
+    )?;
+    // Databases created before `uid` existed still carry the old table shape.
+    if !has_column(tx, "events", "uid")? {
+        tx.execute("ALTER TABLE events ADD COLUMN uid TEXT", [])?;
+    }
+    tx.execute(
+        "CREATE UNIQUE INDEX IF NOT EXISTS idx_events_uid ON events(uid) WHERE uid IS NOT NULL",
+        [],
+    )?;
+    Ok(())
+}
+
+fn add_recurrence_columns(tx: &Transaction<'_>) -> rusqlite::Result<()> {
+    tx.execute_batch(
+        r#"
+        ALTER TABLE events ADD COLUMN rrule TEXT;
+        ALTER TABLE events ADD COLUMN tzid TEXT;
+        "#,
+    )
+}
+
+fn add_recurrence_exceptions(tx: &Transaction<'_>) -> rusqlite::Result<()> {
+    tx.execute_batch(
+        r#"
+        CREATE TABLE event_exdates (
+            event_id INTEGER NOT NULL,
+            occurrence_start TEXT NOT NULL,
+            UNIQUE (event_id, occurrence_start),
+            FOREIGN KEY (event_id) REFERENCES events(id) ON DELETE CASCADE
+        );
+        ALTER TABLE events ADD COLUMN parent_id INTEGER REFERENCES events(id) ON DELETE CASCADE;
+        ALTER TABLE events ADD COLUMN recurrence_id TEXT;
+        CREATE UNIQUE INDEX idx_events_recurrence_id
+            ON events(parent_id, recurrence_id) WHERE parent_id IS NOT NULL;
+        "#,
+    )
+}
+
+/// External-content FTS5 table over `events`, kept in step by triggers. The
+/// trigram tokenizer gives substring matches, which also works for text
+/// without spaces between words (Japanese titles, for one).
+fn add_full_text_index(tx: &Transaction<'_>) -> rusqlite::Result<()> {
+    tx.execute_batch(
+        r#"
+        CREATE VIRTUAL TABLE events_fts USING fts5(
+            title, note, content = 'events', content_rowid = 'id', tokenize = 'trigram'
+        );
+        CREATE TRIGGER events_fts_insert AFTER INSERT ON events BEGIN
+            INSERT INTO events_fts (rowid, title, note) VALUES (new.id, new.title, new.note);
+        END;
+        CREATE TRIGGER events_fts_delete AFTER DELETE ON events BEGIN
+            INSERT INTO events_fts (events_fts, rowid, title, note)
+            VALUES ('delete', old.id, old.title, old.note);
+        END;
+        CREATE TRIGGER events_fts_update AFTER UPDATE OF title, note ON events BEGIN
+            INSERT INTO events_fts (events_fts, rowid, title, note)
+            VALUES ('delete', old.id, old.title, old.note);
+            INSERT INTO events_fts (rowid, title, note) VALUES (new.id, new.title, new.note);
+        END;
+        INSERT INTO events_fts (events_fts) VALUES ('rebuild');
+        "#,
+    )
+}
+
+fn add_private_flag(tx: &Transaction<'_>) -> rusqlite::Result<()> {
+    tx.execute(
+        "ALTER TABLE events ADD COLUMN private INTEGER NOT NULL DEFAULT 0",
+        [],
+    )?;
+    Ok(())
+}
+
+fn add_meeting_details(tx: &Transaction<'_>) -> rusqlite::Result<()> {
+    tx.execute_batch(
+        r#"
+        ALTER TABLE events ADD COLUMN location TEXT;
+        ALTER TABLE events ADD COLUMN url TEXT;
+        ALTER TABLE events ADD COLUMN status TEXT;
+        "#,
+    )
+}
+
+fn add_event_alarms(tx: &Transaction<'_>) -> rusqlite::Result<()> {
+    tx.execute_batch(
+        r#"
+        CREATE TABLE event_alarms (
+            event_id INTEGER NOT NULL,
+            minutes_before INTEGER NOT NULL,
+            UNIQUE (event_id, minutes_before),
+            FOREIGN KEY (event_id) REFERENCES events(id) ON DELETE CASCADE
+        );
+        "#,
+    )
+}
+
+/// Reminders already delivered, so a restarted `remind` does not repeat them.
+fn add_fired_alarms(tx: &Transaction<'_>) -> rusqlite::Result<()> {
+    tx.execute_batch(
+        r#"
+        CREATE TABLE fired_alarms (
+            event_id INTEGER NOT NULL,
+            occurrence_start TEXT NOT NULL,
+            minutes_before INTEGER NOT NULL,
+            fired_at TEXT NOT NULL,
+            UNIQUE (event_id, occurrence_start, minutes_before),
+            FOREIGN KEY (event_id) REFERENCES events(id) ON DELETE CASCADE
+        );
+        "#,
+    )
+}
+
+/// `SEQUENCE` and `LAST-MODIFIED` of imported events, so a later import can
+/// tell whether its copy is newer.
+fn add_import_revisions(tx: &Transaction<'_>) -> rusqlite::Result<()> {
+    tx.execute_batch(
+        r#"
+        ALTER TABLE events ADD COLUMN sequence INTEGER NOT NULL DEFAULT 0;
+        ALTER TABLE events ADD COLUMN last_modified TEXT;
+        "#,
+    )
+}
+
+fn has_column(tx: &Transaction<'_>, table: &str, column: &str) -> rusqlite::Result<bool> {
+    let found: Option<i64> = tx
+        .query_row(
+            "SELECT 1 FROM pragma_table_info(?1) WHERE name = ?2",
+            params![table, column],
+            |row| row.get(0),
+        )
+        .optional()?;
+    Ok(found.is_some())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn fresh_database_is_stamped_with_latest_version() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        run(&mut conn).unwrap();
+        assert_eq!(current_version(&conn).unwrap(), latest_version());
+
+        // Running again is a no-op.
+        run(&mut conn).unwrap();
+        assert_eq!(current_version(&conn).unwrap(), latest_version());
+    }
+
+    #[test]
+    fn legacy_database_without_uid_is_upgraded() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("legacy.db");
+        {
+            let conn = Connection::open(&path).unwrap();
+            conn.execute_batch(
+                r#"
+                CREATE TABLE events (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    title TEXT NOT NULL,
+                    starts_at TEXT NOT NULL,
+                    ends_at TEXT NOT NULL,
+                    note TEXT NOT NULL DEFAULT '',
+                    all_day INTEGER NOT NULL DEFAULT 0
+                );
+                INSERT INTO events (title, starts_at, ends_at)
+                VALUES ('Old', '2025-01-01T09:00:00+00:00', '2025-01-01T10:00:00+00:00');
+                "#,
+            )
+            .unwrap();
+        }
+
+        let mut conn = Connection::open(&path).unwrap();
+        run(&mut conn).unwrap();
+
+        let tx = conn.transaction().unwrap();
+        assert!(has_column(&tx, "events", "uid").unwrap());
+        let title: String = tx
+            .query_row("SELECT title FROM events", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(title, "Old");
+    }
+
+    #[test]
+    fn newer_database_is_refused() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        conn.pragma_update(None, "user_version", latest_version() + 1)
+            .unwrap();
+        let err = run(&mut conn).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("newer than this toki-note supports")
+        );
+    }
+}
@@ -0,0 +1,1983 @@
+use std::{fmt, fs, path::PathBuf, str::FromStr};
+
+use anyhow::{Context, Result, anyhow};
+use chrono::{DateTime, Duration, Local, Utc};
+use chrono_tz::Tz;
+use rusqlite::{
+    Connection, OptionalExtension, Row, Transaction, params, params_from_iter, types::Type,
+};
+
+use crate::recurrence::Recurrence;
+
+mod migrations;
+
+const EVENT_COLUMNS: &str = "id, title, starts_at, ends_at, note, all_day, uid, rrule, tzid, \
+     parent_id, recurrence_id, private, location, url, status";
+
+pub struct Storage {
+    conn: Connection,
+}
+
+impl Storage {
+    pub fn new(path: &PathBuf) -> Result<Self> {
+        if let Some(parent) = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+        {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("failed to create {}", parent.display()))?;
+        }
+
+        let mut conn = Connection::open(path)
+            .with_context(|| format!("failed to open database at {}", path.display()))?;
+        // Tags, exceptions and overrides rely on ON DELETE CASCADE.
+        conn.pragma_update(None, "foreign_keys", true)?;
+        migrations::run(&mut conn)
+            .with_context(|| format!("failed to migrate database at {}", path.display()))?;
+        Ok(Self { conn })
+    }
+
+    /// A counter SQLite bumps whenever another connection commits, so a
+    /// long-running view can tell when to reload.
+    pub fn data_version(&self) -> Result<i64> {
+        Ok(self
+            .conn
+            .pragma_query_value(None, "data_version", |row| row.get(0))?)
+    }
+
+    pub fn fetch_event_by_id(&self, id: i64) -> Result<Option<StoredEvent>> {
+        let sql = format!("SELECT {EVENT_COLUMNS} FROM events WHERE id = ?1");
+        let event = self
+            .conn
+            .query_row(&sql, params![id], event_from_row)
+            .optional()?;
+        if let Some(mut event) = event {
+            event.tags = self.load_tags(event.id)?;
+            event.alarms = self.load_alarms(event.id)?;
+            Ok(Some(event))
+        } else {
+            Ok(None)
+        }
+    }
+
+    pub fn fetch_events_by_title(&self, title: &str) -> Result<Vec<StoredEvent>> {
+        let sql = format!("SELECT {EVENT_COLUMNS} FROM events WHERE title = ?1 ORDER BY starts_at");
+        let mut stmt = self.conn.prepare(&sql)?;
+        let mut rows = stmt.query(params![title])?;
+        let mut events = Vec::new();
+        while let Some(row) = rows.next()? {
+            let mut event = event_from_row(row)?;
+            event.tags = self.load_tags(event.id)?;
+            event.alarms = self.load_alarms(event.id)?;
+            events.push(event);
+        }
+        Ok(events)
+    }
+
+    pub fn insert_event(&mut self, new_event: NewEvent) -> Result<i64> {
+        let tx = self.conn.transaction()?;
+        let id = insert_event_row(&tx, new_event)?;
+        tx.commit()?;
+        Ok(id)
+    }
+
+    /// Starts a batch of import writes that land together on
+    /// [`ImportBatch::commit`].
+    pub fn import_batch(&mut self) -> Result<ImportBatch<'_>> {
+        Ok(ImportBatch {
+            conn: &self.conn,
+            tx: Some(self.conn.unchecked_transaction()?),
+        })
+    }
+
+    pub fn delete_by_id(&mut self, id: i64) -> Result<bool> {
+        let affected = self
+            .conn
+            .execute("DELETE FROM events WHERE id = ?1", params![id])?;
+        Ok(affected > 0)
+    }
+
+    pub fn delete_by_title(&mut self, title: &str) -> Result<usize> {
+        let affected = self
+            .conn
+            .execute("DELETE FROM events WHERE title = ?1", params![title])?;
+        Ok(affected)
+    }
+
+    /// Deletes every stored event (whole series included) matched by
+    /// `filter`, returning the removed ids.
+    pub fn delete_matching(&mut self, filter: &EventFilter) -> Result<Vec<i64>> {
+        let ids: Vec<i64> = self
+            .fetch_event_series(filter)?
+            .into_iter()
+            .map(|event| event.id)
+            .collect();
+        let tx = self.conn.transaction()?;
+        for id in &ids {
+            tx.execute("DELETE FROM events WHERE id = ?1", params![id])?;
+        }
+        tx.commit()?;
+        Ok(ids)
+    }
+
+    /// Events matching `filter`, with recurring series expanded into one
+    /// entry per occurrence. Without a range each series is returned once.
+    pub fn fetch_events(&self, filter: &EventFilter) -> Result<Vec<StoredEvent>> {
+        let series = self.fetch_event_series(filter)?;
+        let Some(range) = &filter.range else {
+            return Ok(series);
+        };
+
+        let mut events = Vec::new();
+        for event in series {
+            if event.recurrence.is_some() {
+                events.extend(self.expand_series(&event, range)?);
+            } else {
+                events.push(event);
+            }
+        }
+        events.sort_by(|a, b| a.starts_at.cmp(&b.starts_at));
+        Ok(events)
+    }
+
+    /// Timed events and occurrences overlapping `starts_at..ends_at`, leaving
+    /// out event `ignore` and the overrides of its occurrences.
+    pub fn overlapping_events(
+        &self,
+        starts_at: &str,
+        ends_at: &str,
+        ignore: Option<i64>,
+    ) -> Result<Vec<StoredEvent>> {
+        let filter = EventFilter {
+            range: Some(EventRange {
+                starts_at: starts_at.to_string(),
+                ends_at: ends_at.to_string(),
+                all_day_starts_at: starts_at.to_string(),
+                all_day_ends_at: ends_at.to_string(),
+            }),
+            ..EventFilter::default()
+        };
+        Ok(self
+            .fetch_events(&filter)?
+            .into_iter()
+            .filter(|event| {
+                !event.all_day
+                    && !event.is_cancelled()
+                    && event.starts_at.as_str() < ends_at
+                    && event.ends_at.as_str() > starts_at
+                    && ignore.is_none_or(|id| event.id != id && event.parent_id != Some(id))
+            })
+            .collect())
+    }
+
+    /// Events matching `filter` without expanding recurrences: a series is
+    /// returned once (as stored) when any of its occurrences overlaps.
+    pub fn fetch_event_series(&self, filter: &EventFilter) -> Result<Vec<StoredEvent>> {
+        let mut values = Vec::new();
+        let conditions = filter_conditions(filter, &mut values);
+        let sql = if conditions.is_empty() {
+            format!("SELECT {EVENT_COLUMNS} FROM events ORDER BY starts_at")
+        } else {
+            format!(
+                "SELECT {EVENT_COLUMNS} FROM events WHERE {} ORDER BY starts_at",
+                conditions.join(" AND ")
+            )
+        };
+        let range = filter.range.as_ref();
+
+        if let Some(query) = &filter.query {
+            self.check_query(query)?;
+        }
+        let mut stmt = self.conn.prepare(&sql)?;
+        let mut rows = stmt.query(params_from_iter(&values))?;
+
+        let mut events = Vec::new();
+        let mut tag_stmt = self
+            .conn
+            .prepare("SELECT tag FROM event_tags WHERE event_id = ?1 ORDER BY tag")?;
+
+        while let Some(row) = rows.next()? {
+            let mut event = event_from_row(row)?;
+            if event.recurrence.is_some() {
+                event.exdates = self.load_exdates(event.id)?;
+                if let Some(range) = range
+                    && self.expand_series(&event, range)?.is_empty()
+                {
+                    continue;
+                }
+            }
+
+            let tag_rows = tag_stmt.query_map(params![event.id], |tag_row| tag_row.get(0))?;
+            for tag in tag_rows {
+                event.tags.push(tag?);
+            }
+            event.alarms = self.load_alarms(event.id)?;
+
+            events.push(event);
+        }
+
+        Ok(events)
+    }
+
+    /// Events whose title or note matches the FTS5 `query`, best match
+    /// first, narrowed by `filter`. Series are returned once, as stored.
+    pub fn search(
+        &self,
+        query: &str,
+        filter: &EventFilter,
+        limit: usize,
+    ) -> Result<Vec<SearchHit>> {
+        let short = is_short_query(query);
+        let mut values = vec![if short {
+            like_pattern(query)
+        } else {
+            match_expression(query)
+        }];
+        let mut conditions = filter_conditions(filter, &mut values);
+        let sql = if short {
+            conditions.insert(
+                0,
+                "(title LIKE ?1 ESCAPE '\\' OR note LIKE ?1 ESCAPE '\\')".to_string(),
+            );
+            format!(
+                "SELECT {EVENT_COLUMNS}, title AS hit_title, note AS hit_snippet FROM events \
+                 WHERE {} ORDER BY title LIKE ?1 ESCAPE '\\' DESC, starts_at",
+                conditions.join(" AND ")
+            )
+        } else {
+            let where_clause = if conditions.is_empty() {
+                String::new()
+            } else {
+                format!("WHERE {}", conditions.join(" AND "))
+            };
+            format!(
+                "SELECT {EVENT_COLUMNS}, hit_title, hit_snippet FROM events \
+                 JOIN (SELECT rowid AS hit_id, \
+                              highlight(events_fts, 0, '[', ']') AS hit_title, \
+                              snippet(events_fts, 1, '[', ']', '...', 64) AS hit_snippet, \
+                              bm25(events_fts, 10.0, 1.0) AS hit_rank \
+                       FROM events_fts WHERE events_fts MATCH ?1) ON hit_id = events.id \
+                 {where_clause} ORDER BY hit_rank, starts_at"
+            )
+        };
+
+        let mut stmt = self.conn.prepare(&sql)?;
+        self.check_query(query)?;
+        let mut rows = stmt.query(params_from_iter(&values))?;
+        let mut hits = Vec::new();
+        while let Some(row) = rows.next()? {
+            let mut event = event_from_row(row)?;
+            let mut title: String = row.get("hit_title")?;
+            let mut snippet: String = row.get("hit_snippet")?;
+            if short {
+                title = mark_matches(&title, query);
+                snippet = excerpt(&snippet, query);
+            }
+            if event.recurrence.is_some() {
+                event.exdates = self.load_exdates(event.id)?;
+                if let Some(range) = &filter.range
+                    && self.expand_series(&event, range)?.is_empty()
+                {
+                    continue;
+                }
+            }
+            event.tags = self.load_tags(event.id)?;
+            event.alarms = self.load_alarms(event.id)?;
+            hits.push(SearchHit {
+                event,
+                title,
+                snippet,
+            });
+            if hits.len() == limit {
+                break;
+            }
+        }
+        Ok(hits)
+    }
+
+    pub fn update_event_timing(
+        &mut self,
+        id: i64,
+        starts_at: &str,
+        ends_at: &str,
+        all_day: bool,
+    ) -> Result<bool> {
+        let tx = self.conn.transaction()?;
+        if !move_event_start(&tx, id, starts_at)? {
+            return Ok(false);
+        }
+        tx.execute(
+            "UPDATE events SET ends_at = ?1, all_day = ?2 WHERE id = ?3",
+            params![ends_at, all_day as i32, id],
+        )?;
+        tx.commit()?;
+        Ok(true)
+    }
+
+    /// Applies every change in `update` to event `id` in one transaction.
+    /// Returns `false` when no such event exists.
+    pub fn update_event(&mut self, id: i64, update: &EventUpdate) -> Result<bool> {
+        let tx = self.conn.transaction()?;
+        let exists: Option<i64> = tx
+            .query_row("SELECT 1 FROM events WHERE id = ?1", params![id], |row| {
+                row.get(0)
+            })
+            .optional()?;
+        if exists.is_none() {
+            return Ok(false);
+        }
+
+        if let Some(title) = &update.title {
+            tx.execute(
+                "UPDATE events SET title = ?1 WHERE id = ?2",
+                params![title, id],
+            )?;
+        }
+        if let Some(note) = &update.note {
+            tx.execute(
+                "UPDATE events SET note = ?1 WHERE id = ?2",
+                params![note, id],
+            )?;
+        }
+        if let Some(extra) = &update.append_note {
+            tx.execute(
+                "UPDATE events SET note = CASE WHEN note = '' THEN ?1 \
+                 ELSE note || char(10) || ?1 END WHERE id = ?2",
+                params![extra, id],
+            )?;
+        }
+        if let Some(starts_at) = &update.starts_at {
+            move_event_start(&tx, id, starts_at)?;
+        }
+        if let Some(ends_at) = &update.ends_at {
+            tx.execute(
+                "UPDATE events SET ends_at = ?1 WHERE id = ?2",
+                params![ends_at, id],
+            )?;
+        }
+        if let Some(all_day) = update.all_day {
+            tx.execute(
+                "UPDATE events SET all_day = ?1 WHERE id = ?2",
+                params![all_day as i32, id],
+            )?;
+        }
+        if let Some(private) = update.private {
+            tx.execute(
+                "UPDATE events SET private = ?1 WHERE id = ?2",
+                params![private as i32, id],
+            )?;
+        }
+        if let Some(location) = &update.location {
+            tx.execute(
+                "UPDATE events SET location = NULLIF(?1, '') WHERE id = ?2",
+                params![location, id],
+            )?;
+        }
+        if let Some(url) = &update.url {
+            tx.execute(
+                "UPDATE events SET url = NULLIF(?1, '') WHERE id = ?2",
+                params![url, id],
+            )?;
+        }
+        if let Some(status) = &update.status {
+            tx.execute(
+                "UPDATE events SET status = ?1 WHERE id = ?2",
+                params![status.map(|status| status.as_str()), id],
+            )?;
+        }
+        if update.clear_tags {
+            tx.execute("DELETE FROM event_tags WHERE event_id = ?1", params![id])?;
+        }
+        for tag in &update.remove_tags {
+            tx.execute(
+                "DELETE FROM event_tags WHERE event_id = ?1 AND tag = ?2",
+                params![id, tag.to_lowercase()],
+            )?;
+        }
+        for tag in &update.add_tags {
+            tx.execute(
+                "INSERT OR IGNORE INTO event_tags (event_id, tag) VALUES (?1, ?2)",
+                params![id, tag.to_lowercase()],
+            )?;
+        }
+        if update.clear_alarms {
+            tx.execute("DELETE FROM event_alarms WHERE event_id = ?1", params![id])?;
+        }
+        for minutes in &update.add_alarms {
+            tx.execute(
+                "INSERT OR IGNORE INTO event_alarms (event_id, minutes_before) VALUES (?1, ?2)",
+                params![id, minutes],
+            )?;
+        }
+        tx.commit()?;
+        Ok(true)
+    }
+
+    /// Cancels one occurrence of a series, dropping any override stored for it.
+    pub fn add_exdate(&mut self, event_id: i64, occurrence_start: &str) -> Result<()> {
+        let tx = self.conn.transaction()?;
+        tx.execute(
+            "INSERT OR IGNORE INTO event_exdates (event_id, occurrence_start) VALUES (?1, ?2)",
+            params![event_id, occurrence_start],
+        )?;
+        tx.execute(
+            "DELETE FROM events WHERE parent_id = ?1 AND recurrence_id = ?2",
+            params![event_id, occurrence_start],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Stores `replacement` in place of the occurrence of `parent_id` that
+    /// originally started at `recurrence_id`, updating an earlier override for
+    /// the same occurrence instead of adding a second one.
+    pub fn upsert_occurrence_override(
+        &mut self,
+        parent_id: i64,
+        recurrence_id: &str,
+        replacement: NewEvent,
+    ) -> Result<i64> {
+        let tx = self.conn.transaction()?;
+        let id = upsert_override_row(&tx, parent_id, recurrence_id, replacement)?;
+        tx.commit()?;
+        Ok(id)
+    }
+
+    /// Ends series `id` before the occurrence at `split_at` by storing
+    /// `truncated` as its rule, discards exceptions from that point on, and
+    /// optionally inserts `continuation` as the series that takes over.
+    pub fn split_series(
+        &mut self,
+        id: i64,
+        split_at: &str,
+        truncated: &Recurrence,
+        continuation: Option<NewEvent>,
+    ) -> Result<Option<i64>> {
+        let tx = self.conn.transaction()?;
+        tx.execute(
+            "UPDATE events SET rrule = ?1 WHERE id = ?2",
+            params![truncated.to_string(), id],
+        )?;
+        tx.execute(
+            "DELETE FROM event_exdates WHERE event_id = ?1 AND occurrence_start >= ?2",
+            params![id, split_at],
+        )?;
+        tx.execute(
+            "DELETE FROM events WHERE parent_id = ?1 AND recurrence_id >= ?2",
+            params![id, split_at],
+        )?;
+        let new_id = continuation
+            .map(|event| insert_event_row(&tx, event))
+            .transpose()?;
+        tx.commit()?;
+        Ok(new_id)
+    }
+
+    /// Records that the reminder `minutes_before` the occurrence of `event_id`
+    /// starting at `occurrence_start` went out. Returns `false` when it was
+    /// already recorded, so each reminder is delivered once.
+    pub fn claim_alarm(
+        &mut self,
+        event_id: i64,
+        occurrence_start: &str,
+        minutes_before: i64,
+    ) -> Result<bool> {
+        let inserted = self.conn.execute(
+            "INSERT OR IGNORE INTO fired_alarms \
+             (event_id, occurrence_start, minutes_before, fired_at) VALUES (?1, ?2, ?3, ?4)",
+            params![
+                event_id,
+                occurrence_start,
+                minutes_before,
+                Utc::now().to_rfc3339()
+            ],
+        )?;
+        Ok(inserted == 1)
+    }
+
+    /// Forgets delivered reminders for occurrences that started before `before`.
+    pub fn prune_fired_alarms(&mut self, before: &str) -> Result<usize> {
+        Ok(self.conn.execute(
+            "DELETE FROM fired_alarms WHERE occurrence_start < ?1",
+            params![before],
+        )?)
+    }
+
+    /// Surfaces FTS5 syntax errors up front; they would otherwise only show
+    /// up while stepping through the results, without naming the query.
+    fn check_query(&self, query: &str) -> Result<()> {
+        if is_short_query(query) {
+            return Ok(());
+        }
+        self.conn
+            .query_row(
+                "SELECT 1 FROM events_fts WHERE events_fts MATCH ?1 LIMIT 1",
+                params![match_expression(query)],
+                |row| row.get::<_, i64>(0),
+            )
+            .optional()
+            .with_context(|| format!("invalid search query '{query}'"))?;
+        Ok(())
+    }
+
+    fn load_tags(&self, event_id: i64) -> Result<Vec<String>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT tag FROM event_tags WHERE event_id = ?1 ORDER BY tag")?;
+        let rows = stmt.query_map(params![event_id], |tag_row| tag_row.get(0))?;
+        let mut tags = Vec::new();
+        for tag in rows {
+            tags.push(tag?);
+        }
+        Ok(tags)
+    }
+
+    fn load_alarms(&self, event_id: i64) -> Result<Vec<i64>> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT minutes_before FROM event_alarms WHERE event_id = ?1 \
+             ORDER BY minutes_before DESC",
+        )?;
+        let rows = stmt.query_map(params![event_id], |row| row.get(0))?;
+        let mut alarms = Vec::new();
+        for alarm in rows {
+            alarms.push(alarm?);
+        }
+        Ok(alarms)
+    }
+
+    fn load_exdates(&self, event_id: i64) -> Result<Vec<String>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT occurrence_start FROM event_exdates WHERE event_id = ?1 \
+             ORDER BY occurrence_start",
+        )?;
+        let rows = stmt.query_map(params![event_id], |row| row.get(0))?;
+        let mut exdates = Vec::new();
+        for exdate in rows {
+            exdates.push(exdate?);
+        }
+        Ok(exdates)
+    }
+
+    /// Copies of a recurring `event` for each occurrence inside `range`,
+    /// leaving out cancelled occurrences and those replaced by an override
+    /// (the override row is returned on its own).
+    fn expand_series(&self, event: &StoredEvent, range: &EventRange) -> Result<Vec<StoredEvent>> {
+        let mut stmt = self
+            .conn
+            .prepare_cached("SELECT recurrence_id FROM events WHERE parent_id = ?1")?;
+        let overridden = stmt
+            .query_map(params![event.id], |row| row.get::<_, String>(0))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        let (window_start, window_end) = range.window(event.all_day)?;
+        let start = parse_instant(&event.starts_at)?;
+        let duration = parse_instant(&event.ends_at)?.signed_duration_since(start);
+        Ok(event
+            .occurrence_starts(window_start, window_end)?
+            .into_iter()
+            .filter_map(|instant| {
+                let starts_at = instant.to_rfc3339();
+                if event.exdates.contains(&starts_at) || overridden.contains(&starts_at) {
+                    return None;
+                }
+                Some(StoredEvent {
+                    starts_at,
+                    ends_at: (instant + duration).to_rfc3339(),
+                    ..event.clone()
+                })
+            })
+            .collect())
+    }
+}
+
+/// SQL conditions (joined with AND) for `filter`, appending their positional
+/// values to `values`. Recurring series are only bounded by their first start
+/// here; the caller checks whether an occurrence actually falls inside the
+/// range.
+fn filter_conditions(filter: &EventFilter, values: &mut Vec<String>) -> Vec<String> {
+    let mut bind = |value: &str| {
+        values.push(value.to_string());
+        format!("?{}", values.len())
+    };
+    let mut conditions = Vec::new();
+
+    if let Some(range) = &filter.range {
+        let (start, end) = (bind(&range.starts_at), bind(&range.ends_at));
+        let (all_day_start, all_day_end) =
+            (bind(&range.all_day_starts_at), bind(&range.all_day_ends_at));
+        conditions.push(format!(
+            "((rrule IS NULL AND all_day = 0 AND starts_at < {end} AND ends_at > {start}) \
+              OR (rrule IS NULL AND all_day = 1 AND starts_at < {all_day_end} AND ends_at > {all_day_start}) \
+              OR (rrule IS NOT NULL AND starts_at < max({end}, {all_day_end})))"
+        ));
+    }
+    if !filter.tags.is_empty() {
+        let placeholders: Vec<String> = filter.tags.iter().map(|tag| bind(tag)).collect();
+        let having = if filter.all_tags {
+            format!(" GROUP BY event_id HAVING COUNT(*) = {}", filter.tags.len())
+        } else {
+            String::new()
+        };
+        conditions.push(format!(
+            "id IN (SELECT event_id FROM event_tags WHERE tag IN ({}){having})",
+            placeholders.join(", ")
+        ));
+    }
+    if !filter.exclude_tags.is_empty() {
+        let placeholders: Vec<String> = filter.exclude_tags.iter().map(|tag| bind(tag)).collect();
+        conditions.push(format!(
+            "id NOT IN (SELECT event_id FROM event_tags WHERE tag IN ({}))",
+            placeholders.join(", ")
+        ));
+    }
+    if let Some(query) = &filter.query {
+        if is_short_query(query) {
+            let pattern = bind(&like_pattern(query));
+            conditions.push(format!(
+                "(title LIKE {pattern} ESCAPE '\\' OR note LIKE {pattern} ESCAPE '\\')"
+            ));
+        } else {
+            conditions.push(format!(
+                "id IN (SELECT rowid FROM events_fts WHERE events_fts MATCH {})",
+                bind(&match_expression(query))
+            ));
+        }
+    }
+    conditions
+}
+
+/// The trigram index cannot match terms shorter than three characters (two
+/// kanji are a whole word in Japanese), so such queries fall back to LIKE.
+fn is_short_query(query: &str) -> bool {
+    query.trim().chars().count() < 3
+}
+
+/// `query` as an FTS5 match expression. Each word becomes a quoted phrase,
+/// so everyday searches such as `foo-bar` or `bar.rs` look for the text
+/// instead of failing to parse; queries written with quotes, parentheses or
+/// AND/OR/NOT/NEAR are passed through as FTS5 syntax.
+fn match_expression(query: &str) -> String {
+    let explicit = query.contains(['"', '(', ')'])
+        || query
+            .split_whitespace()
+            .any(|word| matches!(word, "AND" | "OR" | "NOT") || word.starts_with("NEAR"));
+    if explicit {
+        return query.to_string();
+    }
+    query
+        .split_whitespace()
+        .map(|word| format!("\"{word}\""))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn like_pattern(query: &str) -> String {
+    let escaped = query
+        .trim()
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+    format!("%{escaped}%")
+}
+
+/// Wraps every (ASCII case-insensitive) occurrence of `query` in `[`..`]`,
+/// mirroring what FTS5's `highlight()` produces for longer queries.
+fn mark_matches(text: &str, query: &str) -> String {
+    let query = query.trim();
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while !rest.is_empty() {
+        if rest
+            .get(..query.len())
+            .is_some_and(|head| head.eq_ignore_ascii_case(query))
+        {
+            out.push('[');
+            out.push_str(&rest[..query.len()]);
+            out.push(']');
+            rest = &rest[query.len()..];
+        } else {
+            let ch = rest.chars().next().unwrap_or_default();
+            out.push(ch);
+            rest = &rest[ch.len_utf8()..];
+        }
+    }
+    out
+}
+
+/// A short window of `note` around the first match, like `snippet()`.
+fn excerpt(note: &str, query: &str) -> String {
+    const CONTEXT: usize = 24;
+    let lower = note.to_ascii_lowercase();
+    let Some(position) = lower.find(&query.trim().to_ascii_lowercase()) else {
+        return String::new();
+    };
+    let before: Vec<(usize, char)> = note[..position].char_indices().collect();
+    let start = before
+        .len()
+        .checked_sub(CONTEXT)
+        .map_or(0, |index| before[index].0);
+    let end = note[position..]
+        .char_indices()
+        .nth(CONTEXT * 2)
+        .map_or(note.len(), |(offset, _)| position + offset);
+    let prefix = if start > 0 { "..." } else { "" };
+    let suffix = if end < note.len() { "..." } else { "" };
+    format!("{prefix}{}{suffix}", mark_matches(&note[start..end], query))
+}
+
+/// Writes from `import`, applied in one transaction. Dropping a batch
+/// without committing it rolls every write in it back.
+pub struct ImportBatch<'a> {
+    /// Borrowed from the `&mut Storage` that opened the batch, so nothing
+    /// else writes while it is open.
+    conn: &'a Connection,
+    /// Only empty if a checkpoint failed to open the next transaction.
+    tx: Option<Transaction<'a>>,
+}
+
+impl ImportBatch<'_> {
+    pub fn event_id_by_uid(&self, uid: &str) -> Result<Option<i64>> {
+        Ok(self
+            .conn
+            .prepare_cached(
+                "SELECT id FROM events WHERE uid = ?1 AND recurrence_id IS NULL LIMIT 1",
+            )?
+            .query_row(params![uid], |row| row.get(0))
+            .optional()?)
+    }
+
+    /// Revision of the series or one-off event imported with `uid`.
+    pub fn revision_by_uid(&self, uid: &str) -> Result<Option<StoredRevision>> {
+        self.query_revision(
+            "SELECT id, sequence, last_modified, status FROM events \
+             WHERE uid = ?1 AND parent_id IS NULL AND recurrence_id IS NULL LIMIT 1",
+            params![uid],
+        )
+    }
+
+    /// Revision of a changed occurrence imported with `uid` while its series
+    /// was not stored, and so kept as an event of its own.
+    pub fn detached_override_revision(
+        &self,
+        uid: &str,
+        recurrence_id: &str,
+    ) -> Result<Option<StoredRevision>> {
+        self.query_revision(
+            "SELECT id, sequence, last_modified, status FROM events \
+             WHERE uid = ?1 AND recurrence_id = ?2 AND parent_id IS NULL LIMIT 1",
+            params![uid, recurrence_id],
+        )
+    }
+
+    /// Revision of the override stored for one occurrence of `parent_id`.
+    pub fn override_revision(
+        &self,
+        parent_id: i64,
+        recurrence_id: &str,
+    ) -> Result<Option<StoredRevision>> {
+        self.query_revision(
+            "SELECT id, sequence, last_modified, status FROM events \
+             WHERE parent_id = ?1 AND recurrence_id = ?2",
+            params![parent_id, recurrence_id],
+        )
+    }
+
+    fn query_revision(
+        &self,
+        sql: &str,
+        params: impl rusqlite::Params,
+    ) -> Result<Option<StoredRevision>> {
+        let row = self
+            .conn
+            .prepare_cached(sql)?
+            .query_row(params, |row| {
+                Ok((
+                    row.get(0)?,
+                    row.get(1)?,
+                    row.get(2)?,
+                    row.get::<_, Option<String>>(3)?,
+                ))
+            })
+            .optional()?;
+        let Some((id, sequence, last_modified, status)) = row else {
+            return Ok(None);
+        };
+        Ok(Some(StoredRevision {
+            id,
+            sequence,
+            last_modified,
+            status: status.map(|value| value.parse()).transpose()?,
+        }))
+    }
+
+    pub fn insert_event(&mut self, new_event: NewEvent) -> Result<i64> {
+        insert_event_row(self.conn, new_event)
+    }
+
+    /// Overwrites event `id` with `replacement`, keeping its id so overrides
+    /// and delivered reminders stay attached. Overrides are dropped when the
+    /// replacement no longer repeats.
+    pub fn replace_event(&mut self, id: i64, replacement: NewEvent) -> Result<()> {
+        self.conn
+            .prepare_cached(
+                "UPDATE events SET title = ?1, starts_at = ?2, ends_at = ?3, note = ?4, \
+                 all_day = ?5, uid = ?6, rrule = ?7, tzid = ?8, private = ?9, location = ?10, \
+                 url = ?11, status = ?12, sequence = ?13, last_modified = ?14 WHERE id = ?15",
+            )?
+            .execute(params![
+                replacement.title,
+                replacement.starts_at,
+                replacement.ends_at,
+                replacement.note,
+                replacement.all_day as i32,
+                replacement.uid,
+                replacement.recurrence.as_ref().map(|rule| rule.to_string()),
+                replacement.tzid,
+                replacement.private as i32,
+                replacement.location,
+                replacement.url,
+                replacement.status.map(|status| status.as_str()),
+                replacement.sequence,
+                replacement.last_modified,
+                id,
+            ])?;
+        if replacement.recurrence.is_none() {
+            self.conn
+                .execute("DELETE FROM events WHERE parent_id = ?1", params![id])?;
+        }
+        for table in ["event_tags", "event_alarms", "event_exdates"] {
+            self.conn.execute(
+                &format!("DELETE FROM {table} WHERE event_id = ?1"),
+                params![id],
+            )?;
+        }
+        insert_event_details(self.conn, id, replacement)
+    }
+
+    /// See [`Storage::upsert_occurrence_override`].
+    pub fn upsert_occurrence_override(
+        &mut self,
+        parent_id: i64,
+        recurrence_id: &str,
+        replacement: NewEvent,
+    ) -> Result<i64> {
+        upsert_override_row(self.conn, parent_id, recurrence_id, replacement)
+    }
+
+    /// Commits the writes so far and carries on in a fresh transaction, so
+    /// a long import makes progress without holding one open throughout.
+    pub fn checkpoint(&mut self) -> Result<()> {
+        if let Some(tx) = self.tx.take() {
+            tx.commit()?;
+        }
+        self.tx = Some(self.conn.unchecked_transaction()?);
+        Ok(())
+    }
+
+    /// Marks where one file's writes begin, so they can be kept or undone
+    /// as a whole. Checkpoints must wait until the file is done.
+    pub fn begin_file(&mut self) -> Result<()> {
+        self.conn.execute_batch("SAVEPOINT import_file")?;
+        Ok(())
+    }
+
+    pub fn keep_file(&mut self) -> Result<()> {
+        self.conn.execute_batch("RELEASE import_file")?;
+        Ok(())
+    }
+
+    /// Undoes every write since [`Self::begin_file`].
+    pub fn discard_file(&mut self) -> Result<()> {
+        self.conn
+            .execute_batch("ROLLBACK TO import_file; RELEASE import_file")?;
+        Ok(())
+    }
+
+    pub fn commit(mut self) -> Result<()> {
+        match self.tx.take() {
+            Some(tx) => Ok(tx.commit()?),
+            None => Ok(()),
+        }
+    }
+}
+
+fn upsert_override_row(
+    conn: &Connection,
+    parent_id: i64,
+    recurrence_id: &str,
+    mut replacement: NewEvent,
+) -> Result<i64> {
+    conn.execute(
+        "DELETE FROM event_exdates WHERE event_id = ?1 AND occurrence_start = ?2",
+        params![parent_id, recurrence_id],
+    )?;
+    conn.execute(
+        "DELETE FROM events WHERE parent_id = ?1 AND recurrence_id = ?2",
+        params![parent_id, recurrence_id],
+    )?;
+    replacement.parent_id = Some(parent_id);
+    replacement.recurrence_id = Some(recurrence_id.to_string());
+    replacement.recurrence = None;
+    replacement.uid = None;
+    insert_event_row(conn, replacement)
+}
+
+fn insert_event_row(conn: &Connection, new_event: NewEvent) -> Result<i64> {
+    conn.prepare_cached(
+        "INSERT INTO events \
+         (title, starts_at, ends_at, note, all_day, uid, rrule, tzid, parent_id, recurrence_id, \
+          private, location, url, status, sequence, last_modified) \
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16)",
+    )?
+    .execute(params![
+        new_event.title,
+        new_event.starts_at,
+        new_event.ends_at,
+        new_event.note,
+        new_event.all_day as i32,
+        new_event.uid,
+        new_event.recurrence.as_ref().map(|rule| rule.to_string()),
+        new_event.tzid,
+        new_event.parent_id,
+        new_event.recurrence_id,
+        new_event.private as i32,
+        new_event.location,
+        new_event.url,
+        new_event.status.map(|status| status.as_str()),
+        new_event.sequence,
+        new_event.last_modified,
+    ])?;
+    let id = conn.last_insert_rowid();
+    insert_event_details(conn, id, new_event)?;
+    Ok(id)
+}
+
+/// Tags, reminders and cancelled occurrences of event `id`.
+fn insert_event_details(conn: &Connection, id: i64, new_event: NewEvent) -> Result<()> {
+    let mut insert_tag =
+        conn.prepare_cached("INSERT OR IGNORE INTO event_tags (event_id, tag) VALUES (?1, ?2)")?;
+    for tag in new_event.tags {
+        insert_tag.execute(params![id, tag.to_lowercase()])?;
+    }
+    let mut insert_alarm = conn.prepare_cached(
+        "INSERT OR IGNORE INTO event_alarms (event_id, minutes_before) VALUES (?1, ?2)",
+    )?;
+    for minutes in new_event.alarms {
+        insert_alarm.execute(params![id, minutes])?;
+    }
+    let mut insert_exdate = conn.prepare_cached(
+        "INSERT OR IGNORE INTO event_exdates (event_id, occurrence_start) VALUES (?1, ?2)",
+    )?;
+    for exdate in new_event.exdates {
+        insert_exdate.execute(params![id, exdate])?;
+    }
+    Ok(())
+}
+
+fn event_from_row(row: &Row<'_>) -> rusqlite::Result<StoredEvent> {
+    let recurrence = row
+        .get::<_, Option<String>>(7)?
+        .map(|value| value.parse::<Recurrence>())
+        .transpose()
+        .map_err(|err| rusqlite::Error::FromSqlConversionFailure(7, Type::Text, err.into()))?;
+    let status = row
+        .get::<_, Option<String>>(14)?
+        .map(|value| value.parse::<EventStatus>())
+        .transpose()
+        .map_err(|err| rusqlite::Error::FromSqlConversionFailure(14, Type::Text, err.into()))?;
+    Ok(StoredEvent {
+        id: row.get(0)?,
+        title: row.get(1)?,
+        starts_at: row.get(2)?,
+        ends_at: row.get(3)?,
+        note: row.get(4)?,
+        all_day: row.get::<_, i64>(5)? != 0,
+        uid: row.get(6)?,
+        recurrence,
+        tzid: row.get(8)?,
+        parent_id: row.get(9)?,
+        recurrence_id: row.get(10)?,
+        private: row.get::<_, i64>(11)? != 0,
+        location: row.get(12)?,
+        url: row.get(13)?,
+        status,
+        exdates: Vec::new(),
+        tags: Vec::new(),
+        alarms: Vec::new(),
+    })
+}
+
+/// Sets the start of event `id` and shifts its cancelled and changed
+/// occurrences by the same amount, so they stay on the instances they were
+/// made for. Returns `false` when no such event exists.
+fn move_event_start(tx: &Transaction<'_>, id: i64, starts_at: &str) -> Result<bool> {
+    let Some(previous) = tx
+        .query_row(
+            "SELECT starts_at FROM events WHERE id = ?1",
+            params![id],
+            |row| row.get::<_, String>(0),
+        )
+        .optional()?
+    else {
+        return Ok(false);
+    };
+    tx.execute(
+        "UPDATE events SET starts_at = ?1 WHERE id = ?2",
+        params![starts_at, id],
+    )?;
+    let delta = parse_instant(starts_at)? - parse_instant(&previous)?;
+    if delta.is_zero() {
+        return Ok(true);
+    }
+    // Rows are shifted starting from the end they move towards, so no row
+    // lands on a value another has not vacated yet.
+    let order = if delta > Duration::zero() {
+        "DESC"
+    } else {
+        "ASC"
+    };
+    for (select, update) in [
+        (
+            "SELECT occurrence_start FROM event_exdates WHERE event_id = ?1",
+            "UPDATE event_exdates SET occurrence_start = ?1 \
+             WHERE event_id = ?2 AND occurrence_start = ?3",
+        ),
+        (
+            "SELECT recurrence_id FROM events WHERE parent_id = ?1",
+            "UPDATE events SET recurrence_id = ?1 WHERE parent_id = ?2 AND recurrence_id = ?3",
+        ),
+    ] {
+        let references = tx
+            .prepare(&format!("{select} ORDER BY 1 {order}"))?
+            .query_map(params![id], |row| row.get::<_, String>(0))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        for reference in references {
+            let shifted = (parse_instant(&reference)? + delta).to_rfc3339();
+            tx.execute(update, params![shifted, id, reference])?;
+        }
+    }
+    Ok(true)
+}
+
+fn parse_instant(value: &str) -> Result<DateTime<Utc>> {
+    Ok(DateTime::parse_from_rfc3339(value)
+        .with_context(|| format!("invalid timestamp '{value}'"))?
+        .with_timezone(&Utc))
+}
+
+/// One result of [`Storage::search`]: the matching event plus its title and
+/// a note excerpt with the matched terms wrapped in `[`..`]`.
+pub struct SearchHit {
+    pub event: StoredEvent,
+    pub title: String,
+    pub snippet: String,
+}
+
+/// Which events [`Storage::fetch_events`] returns. Tags are compared as
+/// stored (lowercase); `tags` matches events carrying any of them, or all of
+/// them when `all_tags` is set, and `exclude_tags` drops events carrying any.
+#[derive(Clone, Debug, Default)]
+pub struct EventFilter {
+    pub range: Option<EventRange>,
+    pub tags: Vec<String>,
+    pub all_tags: bool,
+    pub exclude_tags: Vec<String>,
+    /// FTS5 query matched against titles and notes.
+    pub query: Option<String>,
+}
+
+/// Window passed to [`Storage::fetch_events`]. Timed events are matched
+/// against the `starts_at..ends_at` instants. All-day events are stored as
+/// UTC-midnight dates, so they are matched against the calendar dates the
+/// window covers (`all_day_starts_at..all_day_ends_at`, also UTC midnights).
+#[derive(Clone, Debug, PartialEq)]
+pub struct EventRange {
+    pub starts_at: String,
+    pub ends_at: String,
+    pub all_day_starts_at: String,
+    pub all_day_ends_at: String,
+}
+
+impl EventRange {
+    fn window(&self, all_day: bool) -> Result<(DateTime<Utc>, DateTime<Utc>)> {
+        let (start, end) = if all_day {
+            (&self.all_day_starts_at, &self.all_day_ends_at)
+        } else {
+            (&self.starts_at, &self.ends_at)
+        };
+        Ok((parse_instant(start)?, parse_instant(end)?))
+    }
+}
+
+#[derive(Default)]
+pub struct NewEvent {
+    pub title: String,
+    pub note: String,
+    pub starts_at: String,
+    pub ends_at: String,
+    pub all_day: bool,
+    pub tags: Vec<String>,
+    pub uid: Option<String>,
+    pub recurrence: Option<Recurrence>,
+    /// IANA zone the recurrence is evaluated in; `None` means the system zone.
+    pub tzid: Option<String>,
+    /// Cancelled occurrence starts (RFC3339, UTC) of a recurring event.
+    pub exdates: Vec<String>,
+    /// Series this row overrides one occurrence of.
+    pub parent_id: Option<i64>,
+    /// Original start of the overridden occurrence (RFC3339, UTC).
+    pub recurrence_id: Option<String>,
+    /// Left out of, or redacted in, RSS and iCalendar exports.
+    pub private: bool,
+    pub location: Option<String>,
+    /// Where to join or read about the event.
+    pub url: Option<String>,
+    pub status: Option<EventStatus>,
+    /// Reminders as minutes before the start (negative means after it).
+    pub alarms: Vec<i64>,
+    /// `SEQUENCE` and `LAST-MODIFIED` (RFC3339, UTC) of an imported event.
+    pub sequence: i64,
+    pub last_modified: Option<String>,
+}
+
+/// The stored copy of an imported event, as far as a re-import needs to
+/// know whether to replace it.
+#[derive(Clone, Debug)]
+pub struct StoredRevision {
+    pub id: i64,
+    pub sequence: i64,
+    pub last_modified: Option<String>,
+    pub status: Option<EventStatus>,
+}
+
+/// Field changes for [`Storage::update_event`]; `None`/empty leaves a field as is.
+#[derive(Debug, Default)]
+pub struct EventUpdate {
+    pub title: Option<String>,
+    pub note: Option<String>,
+    pub append_note: Option<String>,
+    pub starts_at: Option<String>,
+    pub ends_at: Option<String>,
+    pub all_day: Option<bool>,
+    pub private: Option<bool>,
+    /// New location; an empty string clears it. Likewise for `url`.
+    pub location: Option<String>,
+    pub url: Option<String>,
+    /// `Some(None)` clears the status.
+    pub status: Option<Option<EventStatus>>,
+    pub add_tags: Vec<String>,
+    pub remove_tags: Vec<String>,
+    pub clear_tags: bool,
+    /// Reminders to add, in minutes before the start; applied after `clear_alarms`.
+    pub add_alarms: Vec<i64>,
+    pub clear_alarms: bool,
+}
+
+impl EventUpdate {
+    pub fn is_empty(&self) -> bool {
+        self.title.is_none()
+            && self.note.is_none()
+            && self.append_note.is_none()
+            && self.starts_at.is_none()
+            && self.ends_at.is_none()
+            && self.all_day.is_none()
+            && self.private.is_none()
+            && self.location.is_none()
+            && self.url.is_none()
+            && self.status.is_none()
+            && self.add_tags.is_empty()
+            && self.remove_tags.is_empty()
+            && !self.clear_tags
+            && self.add_alarms.is_empty()
+            && !self.clear_alarms
+    }
+}
+
+/// The RFC 5545 `STATUS` values that apply to events.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EventStatus {
+    Tentative,
+    Confirmed,
+    Cancelled,
+}
+
+impl EventStatus {
+    /// The stored (lowercase) spelling; ICS uses it uppercased.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            EventStatus::Tentative => "tentative",
+            EventStatus::Confirmed => "confirmed",
+            EventStatus::Cancelled => "cancelled",
+        }
+    }
+}
+
+impl fmt::Display for EventStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for EventStatus {
+    type Err = anyhow::Error;
+
+    fn from_str(input: &str) -> Result<Self> {
+        match input.trim().to_ascii_lowercase().as_str() {
+            "tentative" => Ok(EventStatus::Tentative),
+            "confirmed" => Ok(EventStatus::Confirmed),
+            "cancelled" | "canceled" => Ok(EventStatus::Cancelled),
+            _ => Err(anyhow!(
+                "unknown status '{input}'; expected tentative, confirmed or cancelled"
+            )),
+        }
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct StoredEvent {
+    pub id: i64,
+    pub title: String,
+    pub starts_at: String,
+    pub ends_at: String,
+    pub note: String,
+    pub all_day: bool,
+    pub uid: Option<String>,
+    pub recurrence: Option<Recurrence>,
+    pub tzid: Option<String>,
+    pub parent_id: Option<i64>,
+    pub recurrence_id: Option<String>,
+    pub private: bool,
+    pub location: Option<String>,
+    pub url: Option<String>,
+    pub status: Option<EventStatus>,
+    /// Cancelled occurrences; only loaded for recurring events.
+    pub exdates: Vec<String>,
+    pub tags: Vec<String>,
+    /// Reminders as minutes before the start, earliest first.
+    pub alarms: Vec<i64>,
+}
+
+impl StoredEvent {
+    /// Cancelled events are kept (imports mark them rather than drop them)
+    /// but never take up time.
+    pub fn is_cancelled(&self) -> bool {
+        self.status == Some(EventStatus::Cancelled)
+    }
+
+    /// Start instants of the series' occurrences overlapping the window,
+    /// ignoring exceptions. Non-recurring events yield their own start when
+    /// they overlap.
+    ///
+    /// All-day series repeat on UTC dates (how all-day rows are stored); timed
+    /// series use their `tzid` when known and the system zone otherwise.
+    pub fn occurrence_starts(
+        &self,
+        window_start: DateTime<Utc>,
+        window_end: DateTime<Utc>,
+    ) -> Result<Vec<DateTime<Utc>>> {
+        let start = parse_instant(&self.starts_at)?;
+        let duration = parse_instant(&self.ends_at)?.signed_duration_since(start);
+        let Some(rule) = &self.recurrence else {
+            let overlaps = start < window_end && start + duration > window_start;
+            return Ok(if overlaps { vec![start] } else { Vec::new() });
+        };
+        Ok(match self.series_zone() {
+            _ if self.all_day => rule.occurrences(&start, duration, window_start, window_end),
+            Some(tz) => rule.occurrences(
+                &start.with_timezone(&tz),
+                duration,
+                window_start,
+                window_end,
+            ),
+            None => rule.occurrences(
+                &start.with_timezone(&Local),
+                duration,
+                window_start,
+                window_end,
+            ),
+        })
+    }
+
+    /// Number of occurrences of the series that start before `instant`.
+    pub fn occurrences_before(&self, instant: DateTime<Utc>) -> Result<u32> {
+        let start = parse_instant(&self.starts_at)?;
+        let Some(rule) = &self.recurrence else {
+            return Ok(u32::from(start < instant));
+        };
+        let mut count = 0;
+        let visit = |occurrence: DateTime<Utc>| {
+            if occurrence >= instant {
+                return false;
+            }
+            count += 1;
+            true
+        };
+        match self.series_zone() {
+            _ if self.all_day => rule.walk(&start, visit),
+            Some(tz) => rule.walk(&start.with_timezone(&tz), visit),
+            None => rule.walk(&start.with_timezone(&Local), visit),
+        }
+        Ok(count)
+    }
+
+    fn series_zone(&self) -> Option<Tz> {
+        self.tzid
+            .as_deref()
+            .and_then(|name| name.parse::<Tz>().ok())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    struct TempStorage {
+        _dir: tempfile::TempDir,
+        storage: Storage,
+    }
+
+    impl TempStorage {
+        fn new() -> Self {
+            let dir = tempdir().expect("temp dir");
+            let path = dir.path().join("db.sqlite");
+            let storage = Storage::new(&path).expect("storage");
+            Self { _dir: dir, storage }
+        }
+    }
+
+    fn sample_event(title: &str, start: &str, end: &str) -> NewEvent {
+        NewEvent {
+            title: title.to_string(),
+            note: String::new(),
+            starts_at: start.to_string(),
+            ends_at: end.to_string(),
+            all_day: false,
+            tags: Vec::new(),
+            ..NewEvent::default()
+        }
+    }
+
+    fn utc_range(start: &str, end: &str) -> EventRange {
+        EventRange {
+            starts_at: start.to_string(),
+            ends_at: end.to_string(),
+            all_day_starts_at: start.to_string(),
+            all_day_ends_at: end.to_string(),
+        }
+    }
+
+    fn within(range: EventRange) -> EventFilter {
+        EventFilter {
+            range: Some(range),
+            ..EventFilter::default()
+        }
+    }
+
+    #[test]
+    fn data_version_changes_on_commits_from_other_connections() {
+        let mut store = TempStorage::new();
+        let path = store._dir.path().join("db.sqlite");
+        let mut other = Storage::new(&path).unwrap();
+
+        let before = store.storage.data_version().unwrap();
+        store
+            .storage
+            .insert_event(sample_event(
+                "Own",
+                "2025-01-01T09:00:00+00:00",
+                "2025-01-01T10:00:00+00:00",
+            ))
+            .unwrap();
+        assert_eq!(store.storage.data_version().unwrap(), before);
+
+        other
+            .insert_event(sample_event(
+                "Other",
+                "2025-01-02T09:00:00+00:00",
+                "2025-01-02T10:00:00+00:00",
+            ))
+            .unwrap();
+        assert_ne!(store.storage.data_version().unwrap(), before);
+    }
+
+    #[test]
+    fn overlapping_events_finds_timed_events_and_occurrences() {
+        let mut store = TempStorage::new();
+        let meeting = store
+            .storage
+            .insert_event(sample_event(
+                "Meeting",
+                "2025-03-03T10:00:00+00:00",
+                "2025-03-03T11:00:00+00:00",
+            ))
+            .unwrap();
+        let mut standup = sample_event(
+            "Standup",
+            "2025-03-01T09:00:00+00:00",
+            "2025-03-01T09:30:00+00:00",
+        );
+        standup.recurrence = Some("FREQ=DAILY".parse().unwrap());
+        store.storage.insert_event(standup).unwrap();
+        let mut holiday = sample_event(
+            "Holiday",
+            "2025-03-03T00:00:00+00:00",
+            "2025-03-04T00:00:00+00:00",
+        );
+        holiday.all_day = true;
+        store.storage.insert_event(holiday).unwrap();
+        let mut cancelled = sample_event(
+            "Cancelled",
+            "2025-03-03T09:30:00+00:00",
+            "2025-03-03T10:30:00+00:00",
+        );
+        cancelled.status = Some(EventStatus::Cancelled);
+        store.storage.insert_event(cancelled).unwrap();
+
+        let titles = |ignore| {
+            store
+                .storage
+                .overlapping_events(
+                    "2025-03-03T09:15:00+00:00",
+                    "2025-03-03T10:00:00+00:00",
+                    ignore,
+                )
+                .unwrap()
+                .into_iter()
+                .map(|event| event.title)
+                .collect::<Vec<_>>()
+        };
+        // Touching end to start is not an overlap, and all-day and cancelled
+        // events never are.
+        assert_eq!(titles(None), vec!["Standup"]);
+
+        let overlaps = store
+            .storage
+            .overlapping_events(
+                "2025-03-03T09:00:00+00:00",
+                "2025-03-03T12:00:00+00:00",
+                Some(meeting),
+            )
+            .unwrap();
+        assert_eq!(overlaps.len(), 1);
+        assert_eq!(overlaps[0].starts_at, "2025-03-03T09:00:00+00:00");
+    }
+
+    #[test]
+    fn insert_event_lowercases_and_deduplicates_tags() {
+        let mut store = TempStorage::new();
+        let mut event = sample_event(
+            "Demo",
+            "2025-01-01T09:00:00+00:00",
+            "2025-01-01T10:00:00+00:00",
+        );
+        event.tags = vec!["Work".into(), "work".into(), "Home".into()];
+        let id = store.storage.insert_event(event).unwrap();
+
+        let events = store.storage.fetch_events(&EventFilter::default()).unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].id, id);
+        assert_eq!(events[0].tags, vec!["home", "work"]);
+    }
+
+    #[test]
+    fn fetch_events_filters_by_day_range() {
+        let mut store = TempStorage::new();
+        let first = sample_event(
+            "Inside",
+            "2025-05-01T09:00:00+00:00",
+            "2025-05-01T10:00:00+00:00",
+        );
+        let second = sample_event(
+            "Outside",
+            "2025-05-03T09:00:00+00:00",
+            "2025-05-03T10:00:00+00:00",
+        );
+        store.storage.insert_event(first).unwrap();
+        store.storage.insert_event(second).unwrap();
+
+        let events = store
+            .storage
+            .fetch_events(&within(utc_range(
+                "2025-05-01T00:00:00+00:00",
+                "2025-05-02T00:00:00+00:00",
+            )))
+            .unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].title, "Inside");
+    }
+
+    #[test]
+    fn all_day_events_match_by_date_in_zoned_ranges() {
+        let mut store = TempStorage::new();
+        let mut holiday = sample_event(
+            "Holiday",
+            "2025-08-10T00:00:00+00:00",
+            "2025-08-11T00:00:00+00:00",
+        );
+        holiday.all_day = true;
+        store.storage.insert_event(holiday).unwrap();
+        let mut eve = sample_event(
+            "Eve",
+            "2025-08-09T00:00:00+00:00",
+            "2025-08-10T00:00:00+00:00",
+        );
+        eve.all_day = true;
+        store.storage.insert_event(eve).unwrap();
+        store
+            .storage
+            .insert_event(sample_event(
+                "Breakfast",
+                "2025-08-09T23:00:00+00:00",
+                "2025-08-10T00:00:00+00:00",
+            ))
+            .unwrap();
+
+        // 2025-08-10 in Asia/Tokyo.
+        let range = EventRange {
+            starts_at: "2025-08-09T15:00:00+00:00".into(),
+            ends_at: "2025-08-10T15:00:00+00:00".into(),
+            all_day_starts_at: "2025-08-10T00:00:00+00:00".into(),
+            all_day_ends_at: "2025-08-11T00:00:00+00:00".into(),
+        };
+        let titles: Vec<String> = store
+            .storage
+            .fetch_events(&within(range))
+            .unwrap()
+            .into_iter()
+            .map(|event| event.title)
+            .collect();
+        assert_eq!(titles, vec!["Breakfast", "Holiday"]);
+    }
+
+    #[test]
+    fn fetch_events_filters_by_tags() {
+        let mut store = TempStorage::new();
+        for (title, tags) in [
+            ("Review", vec!["work", "design"]),
+            ("Standup", vec!["work"]),
+            ("Dentist", vec!["personal"]),
+            ("Untagged", vec![]),
+        ] {
+            let mut event = sample_event(
+                title,
+                "2025-05-01T09:00:00+00:00",
+                "2025-05-01T10:00:00+00:00",
+            );
+            event.tags = tags.into_iter().map(String::from).collect();
+            store.storage.insert_event(event).unwrap();
+        }
+        fn titles(storage: &Storage, filter: EventFilter) -> Vec<String> {
+            let mut titles: Vec<String> = storage
+                .fetch_events(&filter)
+                .unwrap()
+                .into_iter()
+                .map(|event| event.title)
+                .collect();
+            titles.sort();
+            titles
+        }
+
+        let any = EventFilter {
+            tags: vec!["design".into(), "personal".into()],
+            ..EventFilter::default()
+        };
+        assert_eq!(titles(&store.storage, any), vec!["Dentist", "Review"]);
+        let all = EventFilter {
+            tags: vec!["design".into(), "work".into()],
+            all_tags: true,
+            ..EventFilter::default()
+        };
+        assert_eq!(titles(&store.storage, all), vec!["Review"]);
+        let exclude = EventFilter {
+            exclude_tags: vec!["work".into()],
+            ..EventFilter::default()
+        };
+        assert_eq!(titles(&store.storage, exclude), vec!["Dentist", "Untagged"]);
+        let combined = EventFilter {
+            range: Some(utc_range(
+                "2025-05-01T00:00:00+00:00",
+                "2025-05-02T00:00:00+00:00",
+            )),
+            tags: vec!["work".into()],
+            exclude_tags: vec!["design".into()],
+            ..EventFilter::default()
+        };
+        assert_eq!(titles(&store.storage, combined), vec!["Standup"]);
+
+        let removed = store
+            .storage
+            .delete_matching(&EventFilter {
+                tags: vec!["work".into()],
+                ..EventFilter::default()
+            })
+            .unwrap();
+        assert_eq!(removed.len(), 2);
+        assert_eq!(
+            titles(&store.storage, EventFilter::default()),
+            vec!["Dentist", "Untagged"]
+        );
+    }
+
+    #[test]
+    fn search_follows_inserts_updates_and_deletes() {
+        let mut store = TempStorage::new();
+        let mut call = sample_event(
+            "Budget call",
+            "2025-05-01T09:00:00+00:00",
+            "2025-05-01T10:00:00+00:00",
+        );
+        call.note = "Discussed the budget for Q3".into();
+        let call_id = store.storage.insert_event(call).unwrap();
+        let mut lunch = sample_event(
+            "Lunch",
+            "2025-05-02T12:00:00+00:00",
+            "2025-05-02T13:00:00+00:00",
+        );
+        lunch.note = "Somewhere budget-friendly".into();
+        let lunch_id = store.storage.insert_event(lunch).unwrap();
+        let mut meeting = sample_event(
+            "予算会議",
+            "2025-05-03T12:00:00+00:00",
+            "2025-05-03T13:00:00+00:00",
+        );
+        meeting.note = "来期の予算について".into();
+        store.storage.insert_event(meeting).unwrap();
+
+        let all = EventFilter::default();
+        let hits = store.storage.search("budget", &all, 10).unwrap();
+        let ids: Vec<i64> = hits.iter().map(|hit| hit.event.id).collect();
+        assert_eq!(ids, vec![call_id, lunch_id], "title matches rank first");
+        assert_eq!(hits[0].title, "[Budget] call");
+        assert!(hits[1].snippet.contains("[budget]-friendly"));
+
+        let hits = store.storage.search("予算", &all, 10).unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].title, "[予算]会議");
+
+        // Punctuation in a plain word is searched for rather than parsed.
+        let hits = store.storage.search("budget-friendly", &all, 10).unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].event.id, lunch_id);
+        let filter = EventFilter {
+            query: Some("budget-friendly".into()),
+            ..EventFilter::default()
+        };
+        let events = store.storage.fetch_events(&filter).unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].id, lunch_id);
+        let hits = store.storage.search("call OR lunch", &all, 10).unwrap();
+        assert_eq!(hits.len(), 2);
+
+        let update = EventUpdate {
+            note: Some("Picked a place".into()),
+            ..EventUpdate::default()
+        };
+        store.storage.update_event(lunch_id, &update).unwrap();
+        store.storage.delete_by_id(call_id).unwrap();
+        assert!(store.storage.search("budget", &all, 10).unwrap().is_empty());
+
+        let filter = EventFilter {
+            query: Some("place".into()),
+            ..EventFilter::default()
+        };
+        let events = store.storage.fetch_events(&filter).unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].id, lunch_id);
+        assert!(store.storage.search("\"unterminated", &all, 10).is_err());
+    }
+
+    #[test]
+    fn delete_by_title_removes_rows() {
+        let mut store = TempStorage::new();
+        let event_one = sample_event(
+            "Repeat",
+            "2025-01-01T09:00:00+00:00",
+            "2025-01-01T10:00:00+00:00",
+        );
+        let event_two = sample_event(
+            "Repeat",
+            "2025-01-02T09:00:00+00:00",
+            "2025-01-02T10:00:00+00:00",
+        );
+        store.storage.insert_event(event_one).unwrap();
+        store.storage.insert_event(event_two).unwrap();
+
+        let removed = store.storage.delete_by_title("Repeat").unwrap();
+        assert_eq!(removed, 2);
+        assert!(
+            store
+                .storage
+                .fetch_events(&EventFilter::default())
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn import_batches_find_replace_and_roll_back_events() {
+        let mut store = TempStorage::new();
+        let mut event = sample_event(
+            "Has UID",
+            "2025-01-01T09:00:00+00:00",
+            "2025-01-01T10:00:00+00:00",
+        );
+        event.uid = Some("abc-123".into());
+        event.sequence = 2;
+        event.tags = vec!["work".into()];
+        event.alarms = vec![10];
+        let mut batch = store.storage.import_batch().unwrap();
+        let id = batch.insert_event(event).unwrap();
+
+        let stored = batch.revision_by_uid("abc-123").unwrap().unwrap();
+        assert_eq!((stored.id, stored.sequence), (id, 2));
+        assert!(stored.last_modified.is_none());
+        assert!(batch.revision_by_uid("missing").unwrap().is_none());
+
+        let mut moved = sample_event(
+            "Moved",
+            "2025-01-02T09:00:00+00:00",
+            "2025-01-02T10:00:00+00:00",
+        );
+        moved.uid = Some("abc-123".into());
+        moved.sequence = 3;
+        moved.status = Some(EventStatus::Cancelled);
+        moved.tags = vec!["home".into()];
+        batch.replace_event(id, moved).unwrap();
+        let stored = batch.revision_by_uid("abc-123").unwrap().unwrap();
+        assert_eq!(stored.sequence, 3);
+        assert_eq!(stored.status, Some(EventStatus::Cancelled));
+        batch.commit().unwrap();
+
+        let event = store.storage.fetch_event_by_id(id).unwrap().unwrap();
+        assert_eq!(event.title, "Moved");
+        assert_eq!(event.starts_at, "2025-01-02T09:00:00+00:00");
+        assert_eq!(event.tags, vec!["home"]);
+        assert!(event.alarms.is_empty());
+        assert_eq!(
+            store
+                .storage
+                .search("Moved", &EventFilter::default(), 10)
+                .unwrap()
+                .len(),
+            1
+        );
+
+        // An uncommitted batch leaves nothing behind since its last checkpoint.
+        let mut batch = store.storage.import_batch().unwrap();
+        batch
+            .insert_event(sample_event(
+                "Checkpointed",
+                "2025-01-03T09:00:00+00:00",
+                "2025-01-03T10:00:00+00:00",
+            ))
+            .unwrap();
+        batch.checkpoint().unwrap();
+        batch
+            .insert_event(sample_event(
+                "Rolled back",
+                "2025-01-03T09:00:00+00:00",
+                "2025-01-03T10:00:00+00:00",
+            ))
+            .unwrap();
+        drop(batch);
+        assert!(
+            store
+                .storage
+                .fetch_events_by_title("Rolled back")
+                .unwrap()
+                .is_empty()
+        );
+        assert_eq!(
+            store
+                .storage
+                .fetch_events_by_title("Checkpointed")
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn fetch_events_expands_recurring_series() {
+        let mut store = TempStorage::new();
+        let mut event = sample_event(
+            "Standup",
+            "2025-05-05T09:00:00+00:00",
+            "2025-05-05T09:15:00+00:00",
+        );
+        event.recurrence = Some("FREQ=WEEKLY;BYDAY=MO,TH".parse().unwrap());
+        event.tzid = Some("UTC".into());
+        store.storage.insert_event(event).unwrap();
+
+        let events = store
+            .storage
+            .fetch_events(&within(utc_range(
+                "2025-05-06T00:00:00+00:00",
+                "2025-05-13T00:00:00+00:00",
+            )))
+            .unwrap();
+        let starts: Vec<&str> = events.iter().map(|e| e.starts_at.as_str()).collect();
+        assert_eq!(
+            starts,
+            vec!["2025-05-08T09:00:00+00:00", "2025-05-12T09:00:00+00:00"]
+        );
+
+        let series = store
+            .storage
+            .fetch_event_series(&within(utc_range(
+                "2025-05-06T00:00:00+00:00",
+                "2025-05-13T00:00:00+00:00",
+            )))
+            .unwrap();
+        assert_eq!(series.len(), 1);
+        assert_eq!(series[0].starts_at, "2025-05-05T09:00:00+00:00");
+    }
+
+    #[test]
+    fn exceptions_replace_or_cancel_occurrences() {
+        let mut store = TempStorage::new();
+        let mut event = sample_event(
+            "Standup",
+            "2025-05-05T09:00:00+00:00",
+            "2025-05-05T09:15:00+00:00",
+        );
+        event.recurrence = Some("FREQ=DAILY;COUNT=3".parse().unwrap());
+        event.tzid = Some("UTC".into());
+        let id = store.storage.insert_event(event).unwrap();
+
+        store
+            .storage
+            .add_exdate(id, "2025-05-06T09:00:00+00:00")
+            .unwrap();
+        let moved = sample_event(
+            "Standup",
+            "2025-05-07T10:00:00+00:00",
+            "2025-05-07T10:15:00+00:00",
+        );
+        let override_id = store
+            .storage
+            .upsert_occurrence_override(id, "2025-05-07T09:00:00+00:00", moved)
+            .unwrap();
+
+        let events = store
+            .storage
+            .fetch_events(&within(utc_range(
+                "2025-05-05T00:00:00+00:00",
+                "2025-05-08T00:00:00+00:00",
+            )))
+            .unwrap();
+        let starts: Vec<(i64, &str)> = events
+            .iter()
+            .map(|e| (e.id, e.starts_at.as_str()))
+            .collect();
+        assert_eq!(
+            starts,
+            vec![
+                (id, "2025-05-05T09:00:00+00:00"),
+                (override_id, "2025-05-07T10:00:00+00:00"),
+            ]
+        );
+
+        // Removing the series takes its override with it.
+        store.storage.delete_by_id(id).unwrap();
+        assert!(
+            store
+                .storage
+                .fetch_events(&EventFilter::default())
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn moving_a_series_moves_its_exceptions() {
+        let mut store = TempStorage::new();
+        let mut event = sample_event(
+            "Standup",
+            "2025-05-05T09:00:00+00:00",
+            "2025-05-05T09:15:00+00:00",
+        );
+        event.recurrence = Some("FREQ=DAILY;COUNT=4".parse().unwrap());
+        event.tzid = Some("UTC".into());
+        let id = store.storage.insert_event(event).unwrap();
+        for cancelled in ["2025-05-06T09:00:00+00:00", "2025-05-07T09:00:00+00:00"] {
+            store.storage.add_exdate(id, cancelled).unwrap();
+        }
+        let moved = sample_event(
+            "Standup",
+            "2025-05-08T11:00:00+00:00",
+            "2025-05-08T11:15:00+00:00",
+        );
+        let override_id = store
+            .storage
+            .upsert_occurrence_override(id, "2025-05-08T09:00:00+00:00", moved)
+            .unwrap();
+
+        // A day later: each exception moves onto the next occurrence, even
+        // where that one is itself cancelled until it has moved too.
+        assert!(
+            store
+                .storage
+                .update_event_timing(
+                    id,
+                    "2025-05-06T09:00:00+00:00",
+                    "2025-05-06T09:15:00+00:00",
+                    false,
+                )
+                .unwrap()
+        );
+        assert_eq!(
+            store.storage.load_exdates(id).unwrap(),
+            vec!["2025-05-07T09:00:00+00:00", "2025-05-08T09:00:00+00:00"]
+        );
+        let events = store
+            .storage
+            .fetch_events(&within(utc_range(
+                "2025-05-05T00:00:00+00:00",
+                "2025-05-10T00:00:00+00:00",
+            )))
+            .unwrap();
+        let starts: Vec<(i64, &str)> = events
+            .iter()
+            .map(|e| (e.id, e.starts_at.as_str()))
+            .collect();
+        assert_eq!(
+            starts,
+            vec![
+                (id, "2025-05-06T09:00:00+00:00"),
+                (override_id, "2025-05-08T11:00:00+00:00"),
+            ]
+        );
+    }
+
+    #[test]
+    fn update_event_edits_fields_and_tags() {
+        let mut store = TempStorage::new();
+        let mut event = sample_event(
+            "Typo",
+            "2025-01-01T09:00:00+00:00",
+            "2025-01-01T10:00:00+00:00",
+        );
+        event.note = "first".into();
+        event.tags = vec!["work".into(), "old".into()];
+        let id = store.storage.insert_event(event).unwrap();
+
+        let update = EventUpdate {
+            title: Some("Fixed".into()),
+            append_note: Some("second".into()),
+            add_tags: vec!["New".into()],
+            remove_tags: vec!["old".into()],
+            private: Some(true),
+            ..EventUpdate::default()
+        };
+        assert!(store.storage.update_event(id, &update).unwrap());
+        assert!(!store.storage.update_event(id + 1, &update).unwrap());
+
+        let stored = store.storage.fetch_event_by_id(id).unwrap().unwrap();
+        assert_eq!(stored.title, "Fixed");
+        assert_eq!(stored.note, "first\nsecond");
+        assert_eq!(stored.tags, vec!["new", "work"]);
+        assert!(stored.private);
+    }
+}
@@ -1,3 +1,6 @@
+use std::path::Path;
+
+use assert_cmd::Command;
 use assert_cmd::cargo::cargo_bin_cmd;
 use tempfile::tempdir;
 
@@ -9,6 +12,34 @@ fn parse_row_id(output: &[u8]) -> i64 {
         .expect("row id in output")
 }
 
+/// A toki-note command on the database under `data_home`, with the system
+/// zone set to UTC.
+fn toki_note(data_home: &Path) -> Command {
+    let mut cmd = cargo_bin_cmd!("toki-note");
+    cmd.env("XDG_DATA_HOME", data_home).env("TZ", "UTC");
+    cmd
+}
+
+/// Runs toki-note on the database under `data_home` with the system zone
+/// set to UTC, returning its stdout and failing the test if it fails.
+fn run(data_home: &Path, args: &[&str]) -> String {
+    run_in_zone(data_home, "UTC", args)
+}
+
+fn run_in_zone(data_home: &Path, zone: &str, args: &[&str]) -> String {
+    let output = toki_note(data_home)
+        .env("TZ", zone)
+        .args(args)
+        .output()
+        .expect("run toki-note");
+    assert!(
+        output.status.success(),
+        "{args:?} failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
 #[test]
 fn add_and_list_event() {
     let data_home = tempdir().expect("temp dir");
@@ -136,3 +167,1128 @@ fn move_command_updates_event() {
         "expected moved time, got:\n{stdout}"
     );
 }
+
+#[test]
+fn repeating_event_lists_occurrences_and_exports_rrule() {
+    let data_home = tempdir().expect("temp dir");
+
+    run(
+        data_home.path(),
+        &[
+            "add",
+            "--title",
+            "Weekly sync",
+            "--start",
+            "2025-12-01T09:00:00+00:00",
+            "--repeat",
+            "weekly",
+            "--count",
+            "4",
+        ],
+    );
+
+    let stdout = run(
+        data_home.path(),
+        &["list", "--day", "2025-12-15", "--tz", "UTC"],
+    );
+    assert!(
+        stdout.contains("2025-12-15 09:00 UTC") && stdout.contains("repeats: weekly, 4 times"),
+        "expected third occurrence, got:\n{stdout}"
+    );
+
+    let stdout = run(data_home.path(), &["ical"]);
+    assert!(
+        stdout.contains("RRULE:FREQ=WEEKLY;COUNT=4"),
+        "expected RRULE in export, got:\n{stdout}"
+    );
+}
+
+#[test]
+fn local_series_round_trip_in_their_own_zone() {
+    let data_home = tempdir().expect("temp dir");
+    // Monday 08:00 in Tokyo is still Sunday in UTC.
+    run_in_zone(
+        data_home.path(),
+        "Asia/Tokyo",
+        &[
+            "add",
+            "--title",
+            "Weekly",
+            "--date",
+            "2025-08-04",
+            "--time",
+            "08:00",
+            "--repeat",
+            "weekly",
+            "--by-day",
+            "MO",
+        ],
+    );
+    let export = data_home.path().join("export.ics");
+    run_in_zone(
+        data_home.path(),
+        "Asia/Tokyo",
+        &["ical", "--output", export.to_str().unwrap()],
+    );
+    let ics = std::fs::read_to_string(&export).unwrap();
+    assert!(
+        ics.contains("DTSTART;TZID=Asia/Tokyo:20250804T080000"),
+        "got:\n{ics}"
+    );
+
+    let other_home = tempdir().expect("temp dir");
+    run(
+        other_home.path(),
+        &["import", "--path", export.to_str().unwrap()],
+    );
+    let listed = run(
+        other_home.path(),
+        &["list", "--tz", "Asia/Tokyo", "--day", "2025-08-11"],
+    );
+    assert!(listed.contains("2025-08-11 08:00"), "got:\n{listed}");
+}
+
+#[test]
+fn utc_series_keep_their_exdates_across_dst() {
+    let data_home = tempdir().expect("temp dir");
+    let source = data_home.path().join("utc.ics");
+    std::fs::write(
+        &source,
+        "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nBEGIN:VEVENT\r\nUID:utc-weekly\r\n\
+         DTSTART:20250303T090000Z\r\nDTEND:20250303T100000Z\r\n\
+         RRULE:FREQ=WEEKLY;COUNT=6\r\nEXDATE:20250331T090000Z\r\n\
+         SUMMARY:UTC weekly\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n",
+    )
+    .expect("write ics");
+    // Berlin moves to summer time on 30 March, between two occurrences.
+    let berlin = |args: &[&str]| run_in_zone(data_home.path(), "Europe/Berlin", args);
+    berlin(&["import", "--path", source.to_str().unwrap()]);
+
+    let listed = berlin(&[
+        "list",
+        "--tz",
+        "UTC",
+        "--from",
+        "2025-03-29",
+        "--to",
+        "2025-04-12",
+    ]);
+    assert!(!listed.contains("2025-03-31"), "got:\n{listed}");
+    assert!(listed.contains("2025-04-07 09:00 UTC"), "got:\n{listed}");
+}
+
+#[cfg(unix)]
+#[test]
+fn interactive_edit_runs_the_editor_and_keeps_the_buffer_on_failure() {
+    let data_home = tempdir().expect("temp dir");
+    let id = parse_row_id(
+        run(
+            data_home.path(),
+            &[
+                "add",
+                "--title",
+                "Standup",
+                "--date",
+                "2025-12-01",
+                "--time",
+                "09:00",
+            ],
+        )
+        .as_bytes(),
+    )
+    .to_string();
+
+    // Editor arguments are split without a shell.
+    toki_note(data_home.path())
+        .env("VISUAL", "perl -pi -e 's/Standup/Retro/'")
+        .args(["edit", "--id", &id, "--interactive"])
+        .assert()
+        .success();
+    assert!(run(data_home.path(), &["list"]).contains("Retro"));
+
+    let output = toki_note(data_home.path())
+        .env("VISUAL", "false")
+        .args(["edit", "--id", &id, "--interactive"])
+        .output()
+        .expect("run edit");
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let kept = stderr
+        .lines()
+        .find_map(|line| line.split_once("your edits are kept in "))
+        .map(|(_, path)| path.trim())
+        .unwrap_or_else(|| panic!("no kept buffer in:\n{stderr}"));
+    let buffer = std::fs::read_to_string(kept).expect("kept buffer");
+    std::fs::remove_file(kept).expect("remove kept buffer");
+    assert!(buffer.contains("title = \"Retro\""), "got:\n{buffer}");
+}
+
+#[test]
+fn occurrence_exceptions_round_trip_through_ical() {
+    let data_home = tempdir().expect("temp dir");
+    let id = parse_row_id(
+        run(
+            data_home.path(),
+            &[
+                "add",
+                "--title",
+                "Standup",
+                "--start",
+                "2025-12-01T09:00:00+00:00",
+                "--repeat",
+                "daily",
+                "--count",
+                "5",
+            ],
+        )
+        .as_bytes(),
+    )
+    .to_string();
+    run(
+        data_home.path(),
+        &["delete", "--id", &id, "--occurrence", "2025-12-02"],
+    );
+    run(
+        data_home.path(),
+        &[
+            "move",
+            "--id",
+            &id,
+            "--occurrence",
+            "2025-12-03",
+            "--time",
+            "10:00",
+        ],
+    );
+
+    let listed = run(
+        data_home.path(),
+        &["list", "--tz", "UTC", "--day", "2025-12-02"],
+    );
+    assert!(listed.contains("No events found"), "got:\n{listed}");
+    let listed = run(
+        data_home.path(),
+        &["list", "--tz", "UTC", "--day", "2025-12-03"],
+    );
+    assert!(listed.contains("2025-12-03 10:00 UTC"), "got:\n{listed}");
+
+    let export = data_home.path().join("export.ics");
+    run(
+        data_home.path(),
+        &["ical", "--output", export.to_str().unwrap()],
+    );
+    let ics = std::fs::read_to_string(&export).unwrap();
+    assert!(ics.contains("EXDATE:20251202T090000Z"), "got:\n{ics}");
+    assert!(
+        ics.contains("RECURRENCE-ID:20251203T090000Z"),
+        "got:\n{ics}"
+    );
+
+    let other_home = tempdir().expect("temp dir");
+    run(
+        other_home.path(),
+        &["import", "--path", export.to_str().unwrap()],
+    );
+    for (day, expected) in [
+        ("2025-12-02", "No events found"),
+        ("2025-12-03", "2025-12-03 10:00 UTC"),
+        ("2025-12-04", "2025-12-04 09:00 UTC"),
+    ] {
+        let stdout = run(other_home.path(), &["list", "--tz", "UTC", "--day", day]);
+        assert!(stdout.contains(expected), "{day}: got:\n{stdout}");
+    }
+}
+
+#[test]
+fn occurrences_are_picked_by_date_in_the_series_zone() {
+    let data_home = tempdir().expect("temp dir");
+    // Mondays 08:00 in Tokyo fall on Sundays in UTC.
+    let id = parse_row_id(
+        run_in_zone(
+            data_home.path(),
+            "Asia/Tokyo",
+            &[
+                "add",
+                "--title",
+                "Weekly",
+                "--date",
+                "2025-08-04",
+                "--time",
+                "08:00",
+                "--repeat",
+                "weekly",
+                "--by-day",
+                "MO",
+            ],
+        )
+        .as_bytes(),
+    )
+    .to_string();
+    run(
+        data_home.path(),
+        &["delete", "--id", &id, "--occurrence", "2025-08-11"],
+    );
+    let listed = run(
+        data_home.path(),
+        &["list", "--tz", "Asia/Tokyo", "--day", "2025-08-11"],
+    );
+    assert!(listed.contains("No events found"), "got:\n{listed}");
+
+    // "The second Tuesday" cannot follow a move to a Wednesday.
+    let id = parse_row_id(
+        run(
+            data_home.path(),
+            &[
+                "add",
+                "--title",
+                "Monthly",
+                "--date",
+                "2025-09-09",
+                "--time",
+                "10:00",
+                "--repeat",
+                "monthly",
+                "--by-day",
+                "2TU",
+            ],
+        )
+        .as_bytes(),
+    )
+    .to_string();
+    let output = toki_note(data_home.path())
+        .args(["move", "--id", &id, "--occurrence", "2025-10-14"])
+        .args(["--this-and-following", "--date", "2025-10-15"])
+        .output()
+        .expect("run move");
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("numbered weekdays"), "got:\n{stderr}");
+}
+
+#[test]
+fn day_filter_uses_display_timezone() {
+    let data_home = tempdir().expect("temp dir");
+    run(
+        data_home.path(),
+        &[
+            "add",
+            "--title",
+            "Morning meeting",
+            "--start",
+            "2025-08-10T08:00:00+09:00",
+            "--duration",
+            "1h",
+        ],
+    );
+
+    let tokyo = run(
+        data_home.path(),
+        &["list", "--day", "2025-08-10", "--tz", "Asia/Tokyo"],
+    );
+    assert!(
+        tokyo.contains("Morning meeting") && tokyo.contains("2025-08-10 08:00 JST"),
+        "got:\n{tokyo}"
+    );
+    let utc_day = run(
+        data_home.path(),
+        &[
+            "list",
+            "--day",
+            "2025-08-10",
+            "--tz",
+            "Asia/Tokyo",
+            "--utc-day",
+        ],
+    );
+    assert!(utc_day.contains("No events found"), "got:\n{utc_day}");
+}
+
+#[test]
+fn list_and_add_report_machine_readable_output() {
+    let data_home = tempdir().expect("temp dir");
+    let added: serde_json::Value = serde_json::from_str(&run(
+        data_home.path(),
+        &[
+            "add",
+            "--title",
+            "Planning, Q3",
+            "--start",
+            "2025-08-10T08:00:00+09:00",
+            "--tag",
+            "work",
+            "--format",
+            "json",
+        ],
+    ))
+    .expect("add --format json");
+    assert_eq!(added["action"], "added");
+    assert_eq!(added["event"]["start"], "2025-08-09T23:00:00+00:00");
+
+    let listed: serde_json::Value = serde_json::from_str(&run(
+        data_home.path(),
+        &["list", "--format", "json", "--tz", "Asia/Tokyo"],
+    ))
+    .expect("list --format json");
+    let event = &listed[0];
+    assert_eq!(event["title"], "Planning, Q3");
+    assert_eq!(event["start_local"], "2025-08-10T08:00:00+09:00");
+    assert_eq!(event["tags"], serde_json::json!(["work"]));
+
+    let csv = run(data_home.path(), &["list", "--format", "csv"]);
+    let mut lines = csv.lines();
+    assert_eq!(
+        lines.next(),
+        Some(
+            "id,uid,title,start,end,start_local,end_local,all_day,tags,note,private,location,url,status,reminders"
+        )
+    );
+    assert!(
+        lines.next().unwrap().contains("\"Planning, Q3\""),
+        "got:\n{csv}"
+    );
+}
+
+#[test]
+fn cal_and_week_show_stored_events() {
+    let data_home = tempdir().expect("temp dir");
+
+    run(
+        data_home.path(),
+        &[
+            "add",
+            "--title",
+            "Retro",
+            "--start",
+            "2025-12-03T10:00:00Z",
+            "--end",
+            "2025-12-03T11:00:00Z",
+        ],
+    );
+
+    let stdout = run(
+        data_home.path(),
+        &["cal", "2025-12", "--tz", "UTC", "--width", "84"],
+    );
+    assert!(stdout.contains("December 2025"), "got:\n{stdout}");
+    assert!(
+        stdout.contains("  3*"),
+        "expected Dec 3 to be marked:\n{stdout}"
+    );
+    assert!(stdout.contains("10:00 Retro"), "got:\n{stdout}");
+
+    let stdout = run(
+        data_home.path(),
+        &[
+            "week",
+            "2025-12-05",
+            "--tz",
+            "Asia/Tokyo",
+            "--week-start",
+            "sunday",
+            "--width",
+            "90",
+        ],
+    );
+    assert!(stdout.contains("Sun 11/30"), "got:\n{stdout}");
+    assert!(
+        stdout
+            .lines()
+            .any(|line| line.starts_with("19:00") && line.contains("19:00 Retro")),
+        "expected the event at 19:00 Tokyo time:\n{stdout}"
+    );
+}
+
+#[test]
+fn add_warns_about_overlaps_and_conflicts_lists_them() {
+    let data_home = tempdir().expect("temp dir");
+    let add = |title: &str, start: &str, end: &str| {
+        let mut cmd = toki_note(data_home.path());
+        cmd.args(["add", "--title", title, "--start", start, "--end", end]);
+        cmd
+    };
+
+    add("Planning", "2025-10-06T09:00:00Z", "2025-10-06T10:00:00Z")
+        .assert()
+        .success();
+
+    let output = add("Interview", "2025-10-06T09:30:00Z", "2025-10-06T10:30:00Z")
+        .arg("--strict")
+        .output()
+        .expect("run add --strict");
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("#1 Planning"));
+
+    let output = add("Interview", "2025-10-06T09:30:00Z", "2025-10-06T10:30:00Z")
+        .output()
+        .expect("run add");
+    assert!(output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("warning: overlaps #1 Planning"),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let output = add("Lunch", "2025-10-06T12:00:00Z", "2025-10-06T13:00:00Z")
+        .arg("--allow-overlap")
+        .output()
+        .expect("run add --allow-overlap");
+    assert!(output.stderr.is_empty());
+
+    let stdout = run(
+        data_home.path(),
+        &["conflicts", "--day", "2025-10-06", "--tz", "UTC"],
+    );
+    assert!(
+        stdout.contains("#1 Planning  <->  #2 Interview"),
+        "got:\n{stdout}"
+    );
+    assert!(stdout.contains("2025-10-06 09:30 -> 10:00 UTC (30 min)"));
+    assert!(stdout.contains("1 overlapping pair(s)"));
+}
+
+#[test]
+fn busy_only_and_freebusy_hide_event_details() {
+    let data_home = tempdir().expect("temp dir");
+    run(
+        data_home.path(),
+        &[
+            "add",
+            "--title",
+            "Interview at ACME",
+            "--note",
+            "bring CV",
+            "--start",
+            "2025-10-06T09:00:00Z",
+            "--end",
+            "2025-10-06T10:00:00Z",
+        ],
+    );
+    run(
+        data_home.path(),
+        &[
+            "add",
+            "--title",
+            "Dentist",
+            "--allow-overlap",
+            "--start",
+            "2025-10-06T09:30:00Z",
+            "--end",
+            "2025-10-06T11:00:00Z",
+        ],
+    );
+
+    let ical = run(data_home.path(), &["ical", "--busy-only"]);
+    assert!(ical.contains("SUMMARY:Busy"), "got:\n{ical}");
+    assert!(!ical.contains("ACME") && !ical.contains("CV") && !ical.contains("Dentist"));
+
+    let freebusy = run(
+        data_home.path(),
+        &["freebusy", "--day", "2025-10-06", "--tz", "UTC"],
+    );
+    assert!(freebusy.contains("BEGIN:VFREEBUSY"), "got:\n{freebusy}");
+    assert!(freebusy.contains("DTSTART:20251006T000000Z"));
+    assert!(freebusy.contains("FREEBUSY;FBTYPE=BUSY:20251006T090000Z/20251006T110000Z"));
+    assert!(!freebusy.contains("ACME"));
+}
+
+#[test]
+fn private_events_stay_out_of_feeds() {
+    let data_home = tempdir().expect("temp dir");
+    run(
+        data_home.path(),
+        &[
+            "add",
+            "--title",
+            "Therapy",
+            "--date",
+            "2025-10-06",
+            "--time",
+            "09:00",
+        ],
+    );
+    run(
+        data_home.path(),
+        &[
+            "add",
+            "--title",
+            "Standup",
+            "--date",
+            "2025-10-07",
+            "--time",
+            "09:00",
+        ],
+    );
+    run(data_home.path(), &["edit", "--id", "1", "--private"]);
+    assert!(run(data_home.path(), &["list"]).contains("#1 Therapy (private)"));
+
+    let ical = run(data_home.path(), &["ical"]);
+    assert!(ical.contains("SUMMARY:Standup") && !ical.contains("Therapy"));
+    let ical = run(data_home.path(), &["ical", "--private-events", "busy"]);
+    assert!(ical.contains("CLASS:PRIVATE") && ical.contains("SUMMARY:Busy"));
+    assert!(!ical.contains("Therapy"));
+    assert!(!run(data_home.path(), &["rss"]).contains("Therapy"));
+
+    // CLASS survives a round trip through another database.
+    let export = data_home.path().join("private.ics");
+    run(
+        data_home.path(),
+        &[
+            "ical",
+            "--private-events",
+            "busy",
+            "--output",
+            export.to_str().unwrap(),
+        ],
+    );
+    let other = data_home.path().join("other.db");
+    let other = other.to_str().unwrap();
+    run(
+        data_home.path(),
+        &[
+            "--database",
+            other,
+            "import",
+            "--path",
+            export.to_str().unwrap(),
+        ],
+    );
+    let imported: serde_json::Value = serde_json::from_str(&run(
+        data_home.path(),
+        &["--database", other, "list", "--format", "json"],
+    ))
+    .expect("list --format json");
+    assert_eq!(imported[0]["title"], "Busy");
+    assert_eq!(imported[0]["private"], true);
+
+    run(data_home.path(), &["edit", "--id", "1", "--public"]);
+    assert!(run(data_home.path(), &["ical"]).contains("SUMMARY:Therapy"));
+}
+
+#[test]
+fn location_url_and_status_round_trip_through_ical() {
+    let data_home = tempdir().expect("temp dir");
+    run(
+        data_home.path(),
+        &[
+            "add",
+            "--title",
+            "Design sync",
+            "--date",
+            "2025-10-06",
+            "--time",
+            "10:00",
+            "--location",
+            "Room 4",
+            "--url",
+            "https://meet.example.com/abc",
+            "--status",
+            "tentative",
+        ],
+    );
+    let listed = run(data_home.path(), &["list"]);
+    assert!(
+        listed.contains("#1 Design sync [tentative]"),
+        "got:\n{listed}"
+    );
+    assert!(listed.contains("location: Room 4"));
+    assert!(listed.contains("url: https://meet.example.com/abc"));
+    assert!(run(data_home.path(), &["rss"]).contains("<link>https://meet.example.com/abc</link>"));
+
+    run(
+        data_home.path(),
+        &[
+            "edit",
+            "--id",
+            "1",
+            "--status",
+            "confirmed",
+            "--location",
+            "",
+        ],
+    );
+    let export = data_home.path().join("sync.ics");
+    run(
+        data_home.path(),
+        &["ical", "--output", export.to_str().unwrap()],
+    );
+    let ical = std::fs::read_to_string(&export).expect("read export");
+    assert!(ical.contains("STATUS:CONFIRMED"), "got:\n{ical}");
+    assert!(ical.contains("URL:https://meet.example.com/abc"));
+    assert!(!ical.contains("LOCATION"));
+
+    let other = data_home.path().join("other.db");
+    let other = other.to_str().unwrap();
+    run(
+        data_home.path(),
+        &[
+            "--database",
+            other,
+            "import",
+            "--path",
+            export.to_str().unwrap(),
+        ],
+    );
+    let imported: serde_json::Value = serde_json::from_str(&run(
+        data_home.path(),
+        &["--database", other, "list", "--format", "json"],
+    ))
+    .expect("list --format json");
+    assert_eq!(imported[0]["status"], "confirmed");
+    assert_eq!(imported[0]["url"], "https://meet.example.com/abc");
+    assert_eq!(imported[0]["location"], serde_json::Value::Null);
+}
+
+#[test]
+fn reminders_round_trip_through_valarm() {
+    let data_home = tempdir().expect("temp dir");
+    run(
+        data_home.path(),
+        &[
+            "add",
+            "--title",
+            "Flight",
+            "--start",
+            "2025-10-06T10:00:00Z",
+            "--duration",
+            "2h",
+            "--remind",
+            "90m",
+            "--remind",
+            "1d",
+        ],
+    );
+    assert!(run(data_home.path(), &["list"]).contains("reminders: 1day before, 1h 30m before"));
+    let ical = run(data_home.path(), &["ical"]);
+    assert!(ical.contains("BEGIN:VALARM"), "got:\n{ical}");
+    assert!(ical.contains("TRIGGER:-P1D") && ical.contains("TRIGGER:-PT1H30M"));
+
+    // Triggers relative to the end or at a fixed time are read too.
+    let source = data_home.path().join("alarms.ics");
+    std::fs::write(
+        &source,
+        "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nBEGIN:VEVENT\r\nUID:alarm-test\r\n\
+         DTSTART:20251007T090000Z\r\nDTEND:20251007T100000Z\r\nSUMMARY:Review\r\n\
+         BEGIN:VALARM\r\nACTION:DISPLAY\r\nTRIGGER;RELATED=END:-PT75M\r\nEND:VALARM\r\n\
+         BEGIN:VALARM\r\nACTION:DISPLAY\r\nTRIGGER;VALUE=DATE-TIME:20251007T084000Z\r\n\
+         END:VALARM\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n",
+    )
+    .expect("write ics");
+    run(
+        data_home.path(),
+        &["import", "--path", source.to_str().unwrap()],
+    );
+    let listed: serde_json::Value =
+        serde_json::from_str(&run(data_home.path(), &["list", "--format", "json"]))
+            .expect("list --format json");
+    assert_eq!(listed[1]["title"], "Review");
+    assert_eq!(listed[1]["reminders"], serde_json::json!([20, 15]));
+}
+
+#[test]
+fn remind_check_delivers_each_reminder_once() {
+    let data_home = tempdir().expect("temp dir");
+    let soon = (chrono::Utc::now() + chrono::Duration::minutes(5)).to_rfc3339();
+    run(
+        data_home.path(),
+        &[
+            "add",
+            "--title",
+            "Stand-up; `rm`",
+            "--start",
+            &soon,
+            "--remind",
+            "10m",
+        ],
+    );
+    let check = [
+        "remind",
+        "--check",
+        "--command",
+        r#"echo "fired {title} $TOKI_NOTE_MINUTES""#,
+    ];
+    assert_eq!(run(data_home.path(), &check), "fired Stand-up; `rm` 10\n");
+    assert_eq!(run(data_home.path(), &check), "");
+
+    // Without a command the reminder is printed.
+    run(
+        data_home.path(),
+        &["add", "--title", "Lunch", "--start", &soon],
+    );
+    let printed = run(
+        data_home.path(),
+        &["remind", "--check", "--default-offset", "15m"],
+    );
+    assert!(
+        printed.starts_with("Reminder: #2 Lunch at "),
+        "got:\n{printed}"
+    );
+    assert!(printed.trim_end().ends_with("(15m before)"));
+}
+
+#[test]
+fn reimported_overrides_without_a_series_are_matched_by_recurrence_id() {
+    let data_home = tempdir().expect("temp dir");
+    let source = data_home.path().join("moved.ics");
+    let write = |sequence: u32, title: &str| {
+        std::fs::write(
+            &source,
+            format!(
+                "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nBEGIN:VEVENT\r\nUID:weekly\r\n\
+                 RECURRENCE-ID:20251007T090000Z\r\nSEQUENCE:{sequence}\r\n\
+                 DTSTART:20251007T130000Z\r\nDTEND:20251007T140000Z\r\nSUMMARY:{title}\r\n\
+                 END:VEVENT\r\nEND:VCALENDAR\r\n"
+            ),
+        )
+        .expect("write ics");
+    };
+    let import = || {
+        run(
+            data_home.path(),
+            &["import", "--path", source.to_str().unwrap()],
+        )
+    };
+
+    write(0, "Weekly (moved)");
+    assert!(import().starts_with("Imported 1 new, 0 updated, 0 unchanged"));
+    assert!(import().starts_with("Imported 0 new, 0 updated, 1 unchanged"));
+    write(1, "Weekly (moved again)");
+    assert!(import().starts_with("Imported 0 new, 1 updated, 0 unchanged"));
+
+    let listed: serde_json::Value =
+        serde_json::from_str(&run(data_home.path(), &["list", "--format", "json"]))
+            .expect("list --format json");
+    assert_eq!(listed.as_array().map(Vec::len), Some(1));
+    assert_eq!(listed[0]["title"], "Weekly (moved again)");
+}
+
+#[test]
+fn reimport_updates_newer_revisions_and_cancellations() {
+    let data_home = tempdir().expect("temp dir");
+    let source = data_home.path().join("shared.ics");
+    let write = |events: &[&str]| {
+        let body: String = events
+            .iter()
+            .map(|event| format!("BEGIN:VEVENT\r\n{event}END:VEVENT\r\n"))
+            .collect();
+        std::fs::write(
+            &source,
+            format!("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n{body}END:VCALENDAR\r\n"),
+        )
+        .expect("write ics");
+    };
+    let import = || {
+        run(
+            data_home.path(),
+            &["import", "--path", source.to_str().unwrap()],
+        )
+    };
+
+    write(&[
+        "UID:sync\r\nSEQUENCE:0\r\nDTSTART:20251007T090000Z\r\nDTEND:20251007T100000Z\r\nSUMMARY:Sync\r\n",
+        "UID:retro\r\nDTSTART:20251008T090000Z\r\nDTEND:20251008T100000Z\r\nSUMMARY:Retro\r\n",
+    ]);
+    assert!(import().starts_with("Imported 2 new, 0 updated, 0 unchanged, 0 cancelled"));
+    assert!(import().starts_with("Imported 0 new, 0 updated, 2 unchanged, 0 cancelled"));
+
+    write(&[
+        // Moved and renamed upstream.
+        "UID:sync\r\nSEQUENCE:1\r\nDTSTART:20251007T130000Z\r\nDTEND:20251007T140000Z\r\nSUMMARY:Sync (moved)\r\n",
+        "UID:retro\r\nSTATUS:CANCELLED\r\nDTSTART:20251008T090000Z\r\nDTEND:20251008T100000Z\r\nSUMMARY:Retro\r\n",
+        "UID:demo\r\nDTSTART:20251009T090000Z\r\nDTEND:20251009T100000Z\r\nSUMMARY:Demo\r\n",
+    ]);
+    assert!(import().starts_with("Imported 1 new, 1 updated, 0 unchanged, 1 cancelled"));
+    assert!(import().starts_with("Imported 0 new, 0 updated, 3 unchanged, 0 cancelled"));
+
+    let listed: serde_json::Value =
+        serde_json::from_str(&run(data_home.path(), &["list", "--format", "json"]))
+            .expect("list --format json");
+    assert_eq!(listed.as_array().map(Vec::len), Some(3));
+    assert_eq!(listed[0]["title"], "Sync (moved)");
+    assert_eq!(listed[0]["start"], "2025-10-07T13:00:00+00:00");
+    assert_eq!(listed[1]["status"], "cancelled");
+
+    // An older copy does not undo the update.
+    write(&[
+        "UID:sync\r\nSEQUENCE:0\r\nDTSTART:20251007T090000Z\r\nDTEND:20251007T100000Z\r\nSUMMARY:Sync\r\n",
+    ]);
+    assert!(import().starts_with("Imported 0 new, 0 updated, 1 unchanged"));
+}
+
+#[test]
+fn import_dry_run_classifies_without_writing() {
+    let data_home = tempdir().expect("temp dir");
+    let source = data_home.path().join("colleague.ics");
+    let path = source.to_str().unwrap();
+    let calendar = |sequence: u32| {
+        format!(
+            "BEGIN:VCALENDAR\r\nVERSION:2.0\r\n\
+             BEGIN:VEVENT\r\nUID:sync\r\nSEQUENCE:{sequence}\r\nDTSTART:20251007T090000Z\r\n\
+             DTEND:20251007T100000Z\r\nSUMMARY:Sync\r\nEND:VEVENT\r\n\
+             BEGIN:VEVENT\r\nUID:offsite\r\nDTSTART:20251010T090000Z\r\nSUMMARY:Offsite\r\nEND:VEVENT\r\n\
+             BEGIN:VEVENT\r\nUID:broken\r\nDTSTART;TZID=Nowhere/Land:20251011T090000\r\n\
+             SUMMARY:Broken\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n"
+        )
+    };
+    std::fs::write(&source, calendar(0)).expect("write ics");
+
+    let table = run(data_home.path(), &["import", "--path", path, "--dry-run"]);
+    assert!(table.starts_with("ACTION"), "got:\n{table}");
+    assert!(table.contains("Offsite") && table.contains("UID not seen before"));
+    assert!(table.contains("invalid") && table.contains("unknown timezone 'Nowhere/Land'"));
+    assert!(table.contains("Dry run, nothing written: 2 new"));
+    assert!(run(data_home.path(), &["list"]).contains("No events found"));
+
+    run(data_home.path(), &["import", "--path", path]);
+    std::fs::write(&source, calendar(3)).expect("write ics");
+    let plan: serde_json::Value = serde_json::from_str(&run(
+        data_home.path(),
+        &["import", "--path", path, "--dry-run", "--format", "json"],
+    ))
+    .expect("dry-run JSON");
+    let actions: Vec<_> = plan["events"]
+        .as_array()
+        .expect("events")
+        .iter()
+        .map(|event| (event["uid"].as_str(), event["action"].as_str()))
+        .collect();
+    assert_eq!(
+        actions,
+        vec![
+            (Some("sync"), Some("would-update")),
+            (Some("offsite"), Some("duplicate")),
+            (Some("broken"), Some("invalid")),
+        ]
+    );
+    assert_eq!(plan["events"][0]["reason"], "SEQUENCE 3 is newer than 0");
+    assert_eq!(plan["summary"]["updated"], 1);
+
+    // Events planned earlier in the run count, though none were written.
+    let repeated = data_home.path().join("repeated.ics");
+    std::fs::write(
+        &repeated,
+        "BEGIN:VCALENDAR\r\nVERSION:2.0\r\n\
+         BEGIN:VEVENT\r\nUID:weekly\r\nDTSTART:20251013T090000Z\r\n\
+         RRULE:FREQ=WEEKLY;COUNT=4\r\nSUMMARY:Weekly\r\nEND:VEVENT\r\n\
+         BEGIN:VEVENT\r\nUID:weekly\r\nRECURRENCE-ID:20251020T090000Z\r\n\
+         DTSTART:20251020T130000Z\r\nSUMMARY:Weekly (moved)\r\nEND:VEVENT\r\n\
+         BEGIN:VEVENT\r\nUID:review\r\nDTSTART:20251014T090000Z\r\nSUMMARY:Review\r\nEND:VEVENT\r\n\
+         BEGIN:VEVENT\r\nUID:review\r\nDTSTART:20251014T090000Z\r\nSUMMARY:Review\r\nEND:VEVENT\r\n\
+         END:VCALENDAR\r\n",
+    )
+    .expect("write ics");
+    let plan: serde_json::Value = serde_json::from_str(&run(
+        data_home.path(),
+        &[
+            "import",
+            "--path",
+            repeated.to_str().unwrap(),
+            "--dry-run",
+            "--format",
+            "json",
+        ],
+    ))
+    .expect("dry-run JSON");
+    let actions: Vec<_> = plan["events"]
+        .as_array()
+        .expect("events")
+        .iter()
+        .map(|event| (event["title"].as_str(), event["action"].as_str()))
+        .collect();
+    assert_eq!(
+        actions,
+        vec![
+            (Some("Weekly"), Some("new")),
+            (Some("Review"), Some("new")),
+            (Some("Review"), Some("duplicate")),
+            (Some("Weekly (moved)"), Some("new")),
+        ]
+    );
+    assert_eq!(plan["events"][3]["reason"], "occurrence not changed before");
+}
+
+#[test]
+fn atomic_import_rolls_back_on_the_first_bad_event() {
+    let data_home = tempdir().expect("temp dir");
+    let source = data_home.path().join("mixed.ics");
+    std::fs::write(
+        &source,
+        "BEGIN:VCALENDAR\r\nVERSION:2.0\r\n\
+         BEGIN:VEVENT\r\nUID:good\r\nDTSTART:20251007T090000Z\r\nSUMMARY:Good\r\nEND:VEVENT\r\n\
+         BEGIN:VEVENT\r\nUID:bad\r\nDTSTART;TZID=Nowhere/Land:20251008T090000\r\n\
+         SUMMARY:Bad\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n",
+    )
+    .expect("write ics");
+    let import = |extra: &[&str]| {
+        toki_note(data_home.path())
+            .args(["import", "--path", source.to_str().unwrap()])
+            .args(extra)
+            .output()
+            .expect("run import")
+    };
+
+    let output = import(&["--atomic"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("import rolled back"), "got:\n{stderr}");
+    assert!(stderr.contains("unknown timezone 'Nowhere/Land'"));
+    assert!(run(data_home.path(), &["list"]).contains("No events found"));
+
+    let output = import(&["--batch-size", "1"]);
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).starts_with("Imported 1 new"));
+    assert!(run(data_home.path(), &["list"]).contains("Good"));
+}
+
+#[test]
+fn import_reads_stdin_directories_and_globs() {
+    let data_home = tempdir().expect("temp dir");
+    let vdir = data_home.path().join("vdir");
+    std::fs::create_dir(&vdir).expect("create vdir");
+    let calendar = |uid: &str, day: u32| {
+        format!(
+            "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nBEGIN:VEVENT\r\nUID:{uid}\r\n\
+             DTSTART:202510{day:02}T090000Z\r\nSUMMARY:Event {uid}\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n"
+        )
+    };
+    std::fs::write(vdir.join("one.ics"), calendar("one", 1)).expect("write ics");
+    std::fs::write(vdir.join("two.ics"), calendar("two", 2)).expect("write ics");
+    std::fs::write(vdir.join("README.txt"), "not a calendar").expect("write txt");
+
+    let output = toki_note(data_home.path())
+        .args(["import", "--path", "-"])
+        .write_stdin(calendar("piped", 3))
+        .output()
+        .expect("run import");
+    assert!(output.status.success(), "import failed: {output:?}");
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "Imported 1 new, 0 updated, 0 unchanged, 0 cancelled event(s), skipped 0\n"
+    );
+
+    let summary = run(
+        data_home.path(),
+        &["import", "--path", vdir.to_str().unwrap()],
+    );
+    let lines: Vec<_> = summary.lines().collect();
+    assert_eq!(lines.len(), 3, "got:\n{summary}");
+    assert!(
+        lines[0]
+            .ends_with("one.ics: 1 new, 0 updated, 0 unchanged, 0 cancelled event(s), skipped 0")
+    );
+    assert!(
+        lines[1]
+            .ends_with("two.ics: 1 new, 0 updated, 0 unchanged, 0 cancelled event(s), skipped 0")
+    );
+    assert_eq!(
+        lines[2],
+        "Imported 2 new, 0 updated, 0 unchanged, 0 cancelled event(s), skipped 0"
+    );
+
+    let pattern = vdir.join("t*.ics");
+    let summary = run(
+        data_home.path(),
+        &["import", "--path", pattern.to_str().unwrap()],
+    );
+    assert!(summary.contains("two.ics: 0 new, 0 updated, 1 unchanged"));
+    assert!(!summary.contains("one.ics"));
+
+    // A file that breaks off partway is skipped whole, events before the
+    // break included, even when batches would have committed them.
+    std::fs::write(
+        vdir.join("zz-broken.ics"),
+        calendar("first", 4) + "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nUID:cut\r\n",
+    )
+    .expect("write ics");
+    let pattern = vdir.join("zz*.ics");
+    let output = toki_note(data_home.path())
+        .args(["import", "--batch-size", "1", "--path"])
+        .arg(&pattern)
+        .output()
+        .expect("run import");
+    assert!(output.status.success(), "import failed: {output:?}");
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Skipping"));
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Imported 0 new"));
+    assert!(!run(data_home.path(), &["list"]).contains("Event first"));
+}
+
+#[test]
+fn import_resolves_windows_and_vtimezone_zones() {
+    let data_home = tempdir().expect("temp dir");
+    let source = data_home.path().join("outlook.ics");
+    std::fs::write(
+        &source,
+        "BEGIN:VCALENDAR\r\nVERSION:2.0\r\n\
+         BEGIN:VTIMEZONE\r\nTZID:Eastern\r\n\
+         BEGIN:STANDARD\r\nDTSTART:16011104T020000\r\nRRULE:FREQ=YEARLY;BYMONTH=11;BYDAY=1SU\r\n\
+         TZOFFSETFROM:-0400\r\nTZOFFSETTO:-0500\r\nEND:STANDARD\r\n\
+         BEGIN:DAYLIGHT\r\nDTSTART:16010311T020000\r\nRRULE:FREQ=YEARLY;BYMONTH=3;BYDAY=2SU\r\n\
+         TZOFFSETFROM:-0500\r\nTZOFFSETTO:-0400\r\nEND:DAYLIGHT\r\nEND:VTIMEZONE\r\n\
+         BEGIN:VTIMEZONE\r\nTZID:Odd\r\nBEGIN:STANDARD\r\nDTSTART:16010101T000000\r\n\
+         TZOFFSETFROM:+0317\r\nTZOFFSETTO:+0317\r\nEND:STANDARD\r\nEND:VTIMEZONE\r\n\
+         BEGIN:VEVENT\r\nUID:tokyo\r\nDTSTART;TZID=Tokyo Standard Time:20251007T090000\r\n\
+         DTEND;TZID=Tokyo Standard Time:20251007T100000\r\nSUMMARY:Tokyo sync\r\nEND:VEVENT\r\n\
+         BEGIN:VEVENT\r\nUID:summer\r\nDTSTART;TZID=Eastern:20250715T090000\r\n\
+         SUMMARY:Summer review\r\nEND:VEVENT\r\n\
+         BEGIN:VEVENT\r\nUID:winter\r\nDTSTART;TZID=Eastern:20251215T090000\r\n\
+         RRULE:FREQ=WEEKLY\r\nSUMMARY:Winter review\r\nEND:VEVENT\r\n\
+         BEGIN:VEVENT\r\nUID:fallback\r\nDTSTART;TZID=America/New_York:20251102T013000\r\n\
+         SUMMARY:Night shift\r\nEND:VEVENT\r\n\
+         BEGIN:VEVENT\r\nUID:gap\r\nDTSTART;TZID=Eastern:20250309T023000\r\n\
+         SUMMARY:Early shift\r\nEND:VEVENT\r\n\
+         BEGIN:VEVENT\r\nUID:odd\r\nDTSTART;TZID=Odd:20251006T090000\r\n\
+         RRULE:FREQ=WEEKLY\r\nSUMMARY:Odd sync\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n",
+    )
+    .expect("write ics");
+    let path = source.to_str().unwrap();
+
+    let plan: serde_json::Value = serde_json::from_str(&run(
+        data_home.path(),
+        &["import", "--path", path, "--dry-run", "--format", "json"],
+    ))
+    .expect("dry-run JSON");
+    let starts: Vec<_> = plan["events"]
+        .as_array()
+        .expect("events")
+        .iter()
+        .map(|event| (event["uid"].as_str(), event["start"].as_str()))
+        .collect();
+    assert_eq!(
+        starts,
+        vec![
+            (Some("tokyo"), Some("2025-10-07T00:00:00+00:00")),
+            (Some("summer"), Some("2025-07-15T13:00:00+00:00")),
+            (Some("winter"), Some("2025-12-15T14:00:00+00:00")),
+            // 01:30 happens twice that night; the first one counts.
+            (Some("fallback"), Some("2025-11-02T05:30:00+00:00")),
+            // 02:30 is skipped that night; it is read with the offset before.
+            (Some("gap"), Some("2025-03-09T07:30:00+00:00")),
+            (Some("odd"), Some("2025-10-06T05:43:00+00:00")),
+        ]
+    );
+
+    let output = toki_note(data_home.path())
+        .args(["import", "--path", path])
+        .output()
+        .expect("run import");
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Imported 6 new"));
+    // Eastern follows the same rules as America/New_York, so the series
+    // keeps to 09:00 local after clocks change; nothing matches Odd.
+    let warnings = String::from_utf8_lossy(&output.stderr);
+    assert!(!warnings.contains("'Winter review'"), "got:\n{warnings}");
+    assert!(
+        warnings.contains("'Odd sync' repeats in 'Odd'"),
+        "got:\n{warnings}"
+    );
+    let listed: serde_json::Value = serde_json::from_str(&run(
+        data_home.path(),
+        &["list", "--day", "2026-03-16", "--format", "json"],
+    ))
+    .expect("list --format json");
+    let winter = listed
+        .as_array()
+        .expect("events")
+        .iter()
+        .find(|event| event["title"] == "Winter review")
+        .expect("winter review on 16 March");
+    assert_eq!(winter["start"], "2026-03-16T13:00:00+00:00");
+}